@@ -3,7 +3,41 @@ use config::{Config, File};
 use home::home_dir;
 use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
-use std::{fs, path::PathBuf};
+use std::{collections::HashMap, fs, path::PathBuf};
+
+// 检测规则的严重级别，配置了未知的级别时加载配置失败
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    #[default]
+    Error,
+    Warning,
+    // 不检测
+    Off,
+}
+
+impl LintSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LintSeverity::Error => "error",
+            LintSeverity::Warning => "warning",
+            LintSeverity::Off => "off",
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LintRule {
+    pub name: Option<String>,
+    // 规则类型：path_not_exist、max_file_size、no_setuid、non_root_user、shared_library
+    pub kind: String,
+    // path_not_exist的路径匹配
+    pub pattern: Option<String>,
+    // max_file_size的文件大小
+    pub size: Option<ByteSize>,
+    // 严重级别：error（默认）、warning或off
+    pub severity: Option<LintSeverity>,
+}
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DivingConfig {
@@ -13,6 +47,19 @@ pub struct DivingConfig {
     pub lowest_efficiency: Option<f64>,
     pub highest_wasted_bytes: Option<ByteSize>,
     pub highest_user_wasted_percent: Option<f64>,
+    pub allow_foreign_layer: Option<bool>,
+    pub fail_on_incomplete: Option<bool>,
+    pub highest_package_cache_bytes: Option<ByteSize>,
+    pub lint_rules: Option<Vec<LintRule>>,
+    pub secret_scan: Option<bool>,
+    pub secret_patterns: Option<Vec<String>>,
+    pub osv_path: Option<String>,
+    pub highest_vulnerability_count: Option<usize>,
+    // 镜像配置检测项的严重级别：error、warning或off
+    pub config_checks: Option<HashMap<String, LintSeverity>>,
+    pub zstd_levels: Option<Vec<i32>>,
+    pub zstd_sample_size: Option<ByteSize>,
+    pub source_date_epoch: Option<i64>,
 }
 
 pub fn must_load_config() -> &'static DivingConfig {
@@ -82,3 +129,81 @@ pub fn get_highest_user_wasted_percent() -> f64 {
     }
     0.2
 }
+
+// 是否允许从外部地址下载foreign layer
+pub fn get_allow_foreign_layer() -> bool {
+    let config = must_load_config();
+    config.allow_foreign_layer.unwrap_or_default()
+}
+
+// CI模式下分析结果不完整时是否失败
+pub fn get_fail_on_incomplete() -> bool {
+    let config = must_load_config();
+    config.fail_on_incomplete.unwrap_or(true)
+}
+
+// 包管理工具缓存的最大值，未配置则不检测
+pub fn get_highest_package_cache_bytes() -> Option<u64> {
+    let config = must_load_config();
+    config.highest_package_cache_bytes.map(|item| item.0)
+}
+
+// 镜像内容的检测规则
+pub fn get_lint_rules() -> Vec<LintRule> {
+    let config = must_load_config();
+    config.lint_rules.clone().unwrap_or_default()
+}
+
+// 是否检测文件与镜像配置中的敏感信息
+pub fn get_secret_scan() -> bool {
+    let config = must_load_config();
+    config.secret_scan.unwrap_or_default()
+}
+
+// 自定义的敏感信息正则
+pub fn get_secret_patterns() -> Vec<String> {
+    let config = must_load_config();
+    config.secret_patterns.clone().unwrap_or_default()
+}
+
+// osv数据库的目录，未配置则不检测漏洞
+pub fn get_osv_path() -> Option<PathBuf> {
+    let config = must_load_config();
+    config
+        .osv_path
+        .as_ref()
+        .map(|path| get_config_path().join(path))
+}
+
+// 漏洞数的最大值，未配置则不检测
+pub fn get_highest_vulnerability_count() -> Option<usize> {
+    let config = must_load_config();
+    config.highest_vulnerability_count
+}
+
+// 镜像配置检测项的严重级别，未配置的使用默认级别
+pub fn get_config_checks() -> HashMap<String, LintSeverity> {
+    let config = must_load_config();
+    config.config_checks.clone().unwrap_or_default()
+}
+
+// 估算zstd压缩大小的压缩级别，默认为空（不估算）
+pub fn get_zstd_levels() -> Vec<i32> {
+    let config = must_load_config();
+    config.zstd_levels.clone().unwrap_or_default()
+}
+
+// 每个layer估算zstd压缩大小时的采样大小
+pub fn get_zstd_sample_size() -> u64 {
+    let config = must_load_config();
+    config
+        .zstd_sample_size
+        .map(|item| item.0)
+        .unwrap_or(4 * 1024 * 1024)
+}
+
+// 对比文件修改时间的SOURCE_DATE_EPOCH，未配置则从镜像history中获取
+pub fn get_source_date_epoch() -> Option<i64> {
+    let config = must_load_config();
+    config.source_date_epoch
+}
@@ -1,6 +1,10 @@
 mod load_config;
 
 pub use self::load_config::{
-    get_highest_user_wasted_percent, get_highest_wasted_bytes, get_layer_path,
-    get_lowest_efficiency, must_load_config,
+    get_allow_foreign_layer, get_config_checks, get_fail_on_incomplete,
+    get_highest_package_cache_bytes, get_highest_user_wasted_percent,
+    get_highest_vulnerability_count, get_highest_wasted_bytes, get_layer_path, get_lint_rules,
+    get_lowest_efficiency, get_osv_path, get_secret_patterns, get_secret_scan,
+    get_source_date_epoch, get_zstd_levels, get_zstd_sample_size, must_load_config, LintRule,
+    LintSeverity,
 };
@@ -1,7 +1,9 @@
 use crate::dist::{get_static_file, StaticFile};
-use crate::error::HTTPResult;
+use crate::error::{HTTPError, HTTPResult};
 use crate::image::{
-    analyze_docker_image, get_file_content_from_layer, parse_image_info, DockerAnalyzeResult,
+    analyze_docker_image, build_merged_file_tree, diff_image, get_file_content_from_layer,
+    parse_image_info, to_sbom_document, DockerAnalyzeResult, FileTreeItem, ImageDiffResult,
+    ImageFileInfo, ImageSbom,
 };
 use crate::store::get_blob_from_file;
 use axum::response::{IntoResponse, Response};
@@ -12,7 +14,7 @@ use lru::LruCache;
 use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
 use std::num::NonZeroUsize;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 type JSONResult<T> = HTTPResult<Json<T>>;
@@ -21,7 +23,10 @@ pub fn new_router() -> Router {
     Router::new()
         .route("/ping", get(ping))
         .route("/api/analyze", get(analyze))
+        .route("/api/diff", get(diff))
         .route("/api/file", get(get_file))
+        .route("/api/sbom", get(get_sbom))
+        .route("/api/merged-file-tree", get(get_merged_file_tree))
         .route("/api/latest-images", get(get_latest_images))
         .fallback(get(serve))
 }
@@ -34,6 +39,8 @@ async fn ping() -> &'static str {
 #[serde(rename_all = "camelCase")]
 struct AnalyzeParams {
     image: String,
+    // 已知的基础镜像
+    base: Option<String>,
 }
 
 fn get_latest_image_cache() -> &'static Mutex<LruCache<String, String>> {
@@ -49,13 +56,103 @@ fn add_to_latest_image_cache(name: &String) {
     }
 }
 
+// 分析结果中按需使用的数据（叠加后的文件树与sbom）
+struct AnalyzeCache {
+    layer_file_list: Vec<Vec<ImageFileInfo>>,
+    sbom: ImageSbom,
+}
+
+// 最近的分析结果，以镜像与基础镜像为key
+type AnalyzeCacheKey = (String, String);
+
+fn get_analyze_cache() -> &'static Mutex<LruCache<AnalyzeCacheKey, Arc<AnalyzeCache>>> {
+    static ANALYZE_CACHE: OnceCell<Mutex<LruCache<AnalyzeCacheKey, Arc<AnalyzeCache>>>> =
+        OnceCell::new();
+    ANALYZE_CACHE.get_or_init(|| {
+        let c = LruCache::new(NonZeroUsize::new(5).unwrap());
+        Mutex::new(c)
+    })
+}
+// 缓存分析结果中按需使用的数据，layer的文件列表不输出，因此直接取出
+fn add_to_analyze_cache(
+    name: &str,
+    base: &str,
+    result: &mut DockerAnalyzeResult,
+) -> Arc<AnalyzeCache> {
+    let item = Arc::new(AnalyzeCache {
+        layer_file_list: std::mem::take(&mut result.layer_file_list),
+        sbom: result.sbom.clone(),
+    });
+    if let Ok(mut cache) = get_analyze_cache().lock() {
+        cache.put((name.to_string(), base.to_string()), item.clone());
+    }
+    item
+}
+// 获取镜像的分析数据，缓存中不存在时以相同的基础镜像重新分析
+async fn get_analyze_cache_item(name: &str, base: &str) -> HTTPResult<Arc<AnalyzeCache>> {
+    if let Ok(mut cache) = get_analyze_cache().lock() {
+        if let Some(item) = cache.get(&(name.to_string(), base.to_string())) {
+            return Ok(item.clone());
+        }
+    }
+    let mut image_info = parse_image_info(name);
+    image_info.base = base.to_string();
+    let mut result = analyze_docker_image(image_info).await?;
+    Ok(add_to_analyze_cache(name, base, &mut result))
+}
+
 async fn analyze(Query(params): Query<AnalyzeParams>) -> JSONResult<DockerAnalyzeResult> {
-    let image_info = parse_image_info(&params.image);
-    let result = analyze_docker_image(image_info).await?;
+    let base = params.base.unwrap_or_default();
+    let mut image_info = parse_image_info(&params.image);
+    image_info.base = base.clone();
+    let mut result = analyze_docker_image(image_info).await?;
     add_to_latest_image_cache(&params.image);
+    add_to_analyze_cache(&params.image, &base, &mut result);
     Ok(Json(result))
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MergedFileTreeParams {
+    image: String,
+    // 分析时指定的基础镜像
+    base: Option<String>,
+    layer: usize,
+}
+
+// 叠加至该层后的文件树
+async fn get_merged_file_tree(
+    Query(params): Query<MergedFileTreeParams>,
+) -> JSONResult<Vec<FileTreeItem>> {
+    let result = get_analyze_cache_item(&params.image, &params.base.unwrap_or_default()).await?;
+    if params.layer >= result.layer_file_list.len() {
+        return Err(HTTPError::new_with_category_status(
+            &format!("layer {} is out of range", params.layer),
+            "mergedFileTree",
+            400,
+        ));
+    }
+    Ok(Json(build_merged_file_tree(
+        &result.layer_file_list,
+        params.layer,
+    )))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DiffParams {
+    // 对比前的镜像
+    image: String,
+    // 对比后的镜像
+    target: String,
+}
+
+async fn diff(Query(params): Query<DiffParams>) -> JSONResult<ImageDiffResult> {
+    let before = analyze_docker_image(parse_image_info(&params.image)).await?;
+    let after = analyze_docker_image(parse_image_info(&params.target)).await?;
+    Ok(Json(diff_image(&before, &after)))
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct LatestImageResp {
@@ -115,6 +212,40 @@ async fn get_file(Query(params): Query<GetFileParams>) -> HTTPResult<DownloadFil
     })
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GetSbomParams {
+    image: String,
+    // 分析时指定的基础镜像
+    base: Option<String>,
+    // sbom格式：cyclonedx或spdx
+    format: String,
+}
+
+async fn get_sbom(Query(params): Query<GetSbomParams>) -> HTTPResult<DownloadFile> {
+    let result =
+        get_analyze_cache_item(&params.image, &params.base.clone().unwrap_or_default()).await?;
+    let Some(document) = to_sbom_document(&params.format, &params.image, &result.sbom) else {
+        return Err(HTTPError::new_with_category_status(
+            &format!("unsupported sbom format {}", params.format),
+            "sbom",
+            400,
+        ));
+    };
+    let content = serde_json::to_vec_pretty(&document)
+        .map_err(|err| HTTPError::new_with_category(&err.to_string(), "sbom"))?;
+    let name = params
+        .image
+        .split('/')
+        .next_back()
+        .unwrap_or_default()
+        .replace(':', "-");
+    Ok(DownloadFile {
+        name: format!("{name}.{}.json", params.format),
+        content,
+    })
+}
+
 async fn serve(uri: Uri) -> StaticFile {
     let mut filename = &uri.path()[1..];
     // html无版本号，因此不设置缓存
@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::ImageMergedFile;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageCacheInfo {
+    // 包管理工具：apt、apk、pip等
+    pub ecosystem: String,
+    // 缓存目录
+    pub path: String,
+    // 缓存文件的总大小
+    pub size: u64,
+    // 缓存文件数
+    pub file_count: usize,
+    // 最早添加该缓存的layer
+    pub layer_index: usize,
+}
+
+struct CacheRule {
+    ecosystem: &'static str,
+    // 缓存目录，以/开头
+    pattern: &'static str,
+    // 是否可在任意目录下（如用户目录）
+    anywhere: bool,
+}
+
+// 常见包管理工具的缓存目录
+static CACHE_RULES: &[CacheRule] = &[
+    CacheRule {
+        ecosystem: "apt",
+        pattern: "/var/lib/apt/lists/",
+        anywhere: false,
+    },
+    CacheRule {
+        ecosystem: "apt",
+        pattern: "/var/cache/apt/",
+        anywhere: false,
+    },
+    CacheRule {
+        ecosystem: "apk",
+        pattern: "/var/cache/apk/",
+        anywhere: false,
+    },
+    CacheRule {
+        ecosystem: "yum",
+        pattern: "/var/cache/yum/",
+        anywhere: false,
+    },
+    CacheRule {
+        ecosystem: "dnf",
+        pattern: "/var/cache/dnf/",
+        anywhere: false,
+    },
+    CacheRule {
+        ecosystem: "pip",
+        pattern: "/.cache/pip/",
+        anywhere: true,
+    },
+    CacheRule {
+        ecosystem: "npm",
+        pattern: "/.npm/",
+        anywhere: true,
+    },
+    CacheRule {
+        ecosystem: "yarn",
+        pattern: "/.cache/yarn/",
+        anywhere: true,
+    },
+    CacheRule {
+        ecosystem: "cargo",
+        pattern: "/.cargo/registry/",
+        anywhere: true,
+    },
+    CacheRule {
+        ecosystem: "cargo",
+        pattern: "/usr/local/cargo/registry/",
+        anywhere: false,
+    },
+    CacheRule {
+        ecosystem: "go",
+        pattern: "/.cache/go-build/",
+        anywhere: true,
+    },
+    CacheRule {
+        ecosystem: "go",
+        pattern: "/go/pkg/mod/cache/",
+        anywhere: true,
+    },
+];
+
+// 匹配文件所在的缓存目录，返回包管理工具与缓存目录
+fn match_cache_rule(path: &str) -> Option<(&'static str, String)> {
+    let path = format!("/{path}");
+    for rule in CACHE_RULES.iter() {
+        let index = if rule.anywhere {
+            path.find(rule.pattern)
+        } else if path.starts_with(rule.pattern) {
+            Some(0)
+        } else {
+            None
+        };
+        if let Some(index) = index {
+            let end = index + rule.pattern.len() - 1;
+            return Some((rule.ecosystem, path[..end].to_string()));
+        }
+    }
+    None
+}
+
+// 从合并后的文件系统中查找包管理工具的缓存
+pub fn find_package_caches(files: &[ImageMergedFile]) -> Vec<PackageCacheInfo> {
+    let mut caches: BTreeMap<String, PackageCacheInfo> = BTreeMap::new();
+    for file in files.iter() {
+        // 目录与链接忽略
+        if file.info.mode.starts_with('d') || !file.info.link.is_empty() {
+            continue;
+        }
+        let Some((ecosystem, path)) = match_cache_rule(&file.info.path) else {
+            continue;
+        };
+        let cache = caches
+            .entry(path.clone())
+            .or_insert_with(|| PackageCacheInfo {
+                ecosystem: ecosystem.to_string(),
+                path,
+                layer_index: file.layer_index,
+                ..Default::default()
+            });
+        cache.size += file.info.size;
+        cache.file_count += 1;
+        cache.layer_index = cache.layer_index.min(file.layer_index);
+    }
+    let mut package_cache_list: Vec<PackageCacheInfo> =
+        caches.into_values().filter(|item| item.size > 0).collect();
+    package_cache_list.sort_by_key(|item| std::cmp::Reverse(item.size));
+    package_cache_list
+}
@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+
+use super::ImageLayer;
+
+// 采样时分块的数量
+static SAMPLE_CHUNK_COUNT: usize = 16;
+
+// 压缩率高于此值视为未压缩
+static UNCOMPRESSED_RATIO: f64 = 0.95;
+
+// zstd采样压缩率高于此值视为包含已压缩的数据
+static PRECOMPRESSED_RATIO: f64 = 0.9;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZstdEstimate {
+    // zstd的压缩级别
+    pub level: i32,
+    // 预计压缩后的大小
+    pub size: u64,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayerCompression {
+    // 压缩率：layer大小/解压后大小
+    pub ratio: f64,
+    // layer未压缩（tar或压缩无效果）
+    pub uncompressed: bool,
+    // layer中大部分为已压缩的数据，再次压缩无效果
+    pub precompressed: bool,
+    // 用于估算zstd压缩大小的采样数据大小
+    pub sample_size: u64,
+    // 各压缩级别预计的zstd大小（无解压数据时为空）
+    pub zstd_estimate_list: Vec<ZstdEstimate>,
+}
+
+// 从数据中均匀选取分块，总大小不超过sample_size
+fn get_sample_chunks(data: &[u8], sample_size: usize) -> Vec<&[u8]> {
+    if data.len() <= sample_size {
+        return vec![data];
+    }
+    let chunk_size = (sample_size / SAMPLE_CHUNK_COUNT).max(1);
+    let step = (data.len() - chunk_size) / (SAMPLE_CHUNK_COUNT - 1);
+    (0..SAMPLE_CHUNK_COUNT)
+        .map(|index| {
+            let offset = index * step;
+            &data[offset..offset + chunk_size]
+        })
+        .collect()
+}
+
+// 分析layer的压缩率，并采样估算以zstd压缩后的大小
+// tar_data为解压后的数据（使用toc未解压时为空）
+pub fn analyze_compression(
+    media_type: &str,
+    size: u64,
+    unpack_size: u64,
+    tar_data: Option<&[u8]>,
+    levels: &[i32],
+    sample_size: u64,
+) -> LayerCompression {
+    let ratio = if unpack_size == 0 {
+        1.0
+    } else {
+        size as f64 / unpack_size as f64
+    };
+    let mut compression = LayerCompression {
+        ratio,
+        ..Default::default()
+    };
+    if let Some(data) = tar_data.filter(|data| !data.is_empty()) {
+        let chunks = get_sample_chunks(data, sample_size as usize);
+        let sampled: usize = chunks.iter().map(|chunk| chunk.len()).sum();
+        compression.sample_size = sampled as u64;
+        for level in levels.iter() {
+            let mut compressed = 0;
+            for chunk in chunks.iter() {
+                match zstd::bulk::compress(chunk, *level) {
+                    Ok(value) => compressed += value.len(),
+                    // 压缩级别无效等错误时不估算
+                    Err(_) => {
+                        compressed = 0;
+                        break;
+                    }
+                }
+            }
+            if compressed == 0 {
+                continue;
+            }
+            compression.zstd_estimate_list.push(ZstdEstimate {
+                level: *level,
+                size: (compressed as f64 / sampled as f64 * data.len() as f64) as u64,
+            });
+        }
+        // 以最低的压缩级别判断是否为已压缩的数据
+        if let Some(estimate) = compression
+            .zstd_estimate_list
+            .iter()
+            .min_by_key(|item| item.level)
+        {
+            compression.precompressed =
+                estimate.size as f64 / data.len() as f64 > PRECOMPRESSED_RATIO;
+        }
+    }
+    let compressed_media_type = media_type.contains("gzip") || media_type.contains("zstd");
+    compression.uncompressed =
+        !compressed_media_type || (ratio > UNCOMPRESSED_RATIO && !compression.precompressed);
+    compression
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZstdSavings {
+    pub level: i32,
+    // 可估算的layer的当前大小
+    pub size: u64,
+    // 以zstd压缩后预计的大小
+    pub zstd_size: u64,
+    // 拉取时预计可节省的大小（负数则为增加）
+    pub savings: i64,
+}
+
+// 按压缩级别汇总所有layer以zstd压缩后可节省的大小
+pub fn summarize_zstd_savings(layers: &[ImageLayer]) -> Vec<ZstdSavings> {
+    let mut savings_list: Vec<ZstdSavings> = vec![];
+    for layer in layers.iter() {
+        let Some(ref compression) = layer.compression else {
+            continue;
+        };
+        for estimate in compression.zstd_estimate_list.iter() {
+            let index = if let Some(index) = savings_list
+                .iter()
+                .position(|item| item.level == estimate.level)
+            {
+                index
+            } else {
+                savings_list.push(ZstdSavings {
+                    level: estimate.level,
+                    ..Default::default()
+                });
+                savings_list.len() - 1
+            };
+            let savings = &mut savings_list[index];
+            savings.size += layer.size;
+            savings.zstd_size += estimate.size;
+            savings.savings = savings.size as i64 - savings.zstd_size as i64;
+        }
+    }
+    savings_list.sort_by_key(|item| item.level);
+    savings_list
+}
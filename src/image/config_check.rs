@@ -0,0 +1,201 @@
+use once_cell::sync::OnceCell;
+use regex::Regex;
+use std::collections::HashMap;
+
+use crate::config::LintSeverity;
+
+use super::{
+    find_env_secrets, is_root_user, ImageExtraInfo, ImageHistory, LintIssue, MergedFileSystem,
+    ANNOTATION_BASE_NAME,
+};
+
+static CHECK_ROOT_USER: &str = "root_user";
+static CHECK_NO_HEALTHCHECK: &str = "no_healthcheck";
+static CHECK_ENTRYPOINT_MISSING: &str = "entrypoint_missing";
+static CHECK_LATEST_BASE_IMAGE: &str = "latest_base_image";
+static CHECK_ENV_SECRET: &str = "env_secret";
+static CHECK_MISSING_OCI_LABELS: &str = "missing_oci_labels";
+static CHECK_ADD_REMOTE_URL: &str = "add_remote_url";
+
+// 未设置PATH时的默认值
+static DEFAULT_PATH: &str = "/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin";
+
+// 建议设置的OCI标准label
+static OCI_STANDARD_LABELS: &[&str] = &[
+    "org.opencontainers.image.source",
+    "org.opencontainers.image.revision",
+    "org.opencontainers.image.licenses",
+];
+
+pub struct ConfigCheckOption<'a> {
+    // 最终文件系统
+    pub fs: &'a MergedFileSystem,
+    pub config: &'a ImageExtraInfo,
+    pub history: &'a [ImageHistory],
+    // manifest的annotations
+    pub annotations: &'a HashMap<String, String>,
+    // 分析结果不完整时不检测entrypoint是否存在
+    pub incomplete: bool,
+    // 各检测项的严重级别：error、warning或off
+    pub severities: &'a HashMap<String, LintSeverity>,
+}
+
+// 从PATH、工作目录中查找entrypoint的可执行文件是否存在
+fn is_executable_exist(
+    fs: &MergedFileSystem,
+    executable: &str,
+    envs: &[String],
+    dir: &str,
+) -> bool {
+    let candidates: Vec<String> = if executable.starts_with('/') {
+        vec![executable.to_string()]
+    } else if executable.contains('/') {
+        vec![format!("{}/{executable}", dir.trim_end_matches('/'))]
+    } else {
+        envs.iter()
+            .find_map(|item| item.strip_prefix("PATH="))
+            .unwrap_or(DEFAULT_PATH)
+            .split(':')
+            .map(|dir| format!("{}/{executable}", dir.trim_end_matches('/')))
+            .collect()
+    };
+    candidates.iter().any(|path| fs.resolve(path).is_some())
+}
+
+// 基础镜像是否使用latest或未指定tag（指定digest的除外）
+fn is_latest_reference(name: &str) -> bool {
+    if name.contains('@') {
+        return false;
+    }
+    let repo = name.rsplit('/').next().unwrap_or_default();
+    match repo.split_once(':') {
+        Some((_, tag)) => tag == "latest",
+        None => true,
+    }
+}
+
+// 构建历史中ADD远程URL的指令
+fn find_remote_add(history: &[ImageHistory]) -> Vec<String> {
+    static ADD_URL_REGEX: OnceCell<Regex> = OnceCell::new();
+    let regex = ADD_URL_REGEX.get_or_init(|| {
+        Regex::new(r"(?:^|\s)ADD\s+(?:--\S+\s+)*((?:https?|git)://\S+|git@\S+)").unwrap()
+    });
+    history
+        .iter()
+        .filter_map(|item| item.created_by.as_ref())
+        .filter_map(|created_by| regex.captures(created_by))
+        .map(|captures| captures[1].to_string())
+        .collect()
+}
+
+// 检测镜像配置是否符合最佳实践，结果与lint规则一样输出
+pub fn check_image_config(opt: ConfigCheckOption) -> Vec<LintIssue> {
+    let mut issues = vec![];
+    let mut add_issue = |rule: &str, default_severity: LintSeverity, message: String| {
+        let severity = opt
+            .severities
+            .get(rule)
+            .copied()
+            .unwrap_or(default_severity);
+        if severity == LintSeverity::Off {
+            return;
+        }
+        issues.push(LintIssue {
+            rule: rule.to_string(),
+            severity: severity.as_str().to_string(),
+            message,
+            count: 1,
+            ..Default::default()
+        });
+    };
+    let config = opt.config;
+    let user = config.user.clone().unwrap_or_default();
+    let envs = config.env.clone().unwrap_or_default();
+    let labels = config.labels.clone().unwrap_or_default();
+
+    if is_root_user(&user) {
+        add_issue(
+            CHECK_ROOT_USER,
+            LintSeverity::Warning,
+            "container runs as root".to_string(),
+        );
+    }
+
+    let has_healthcheck = config
+        .healthcheck
+        .as_ref()
+        .is_some_and(|item| item.test.first().is_some_and(|test| test != "NONE"));
+    if !has_healthcheck {
+        add_issue(
+            CHECK_NO_HEALTHCHECK,
+            LintSeverity::Warning,
+            "HEALTHCHECK is not defined".to_string(),
+        );
+    }
+
+    let mut command = config.entrypoint.clone().unwrap_or_default();
+    command.extend(config.cmd.clone().unwrap_or_default());
+    if let Some(executable) = command.first().filter(|_| !opt.incomplete) {
+        let dir = config
+            .working_dir
+            .clone()
+            .unwrap_or_else(|| "/".to_string());
+        if !is_executable_exist(opt.fs, executable, &envs, &dir) {
+            add_issue(
+                CHECK_ENTRYPOINT_MISSING,
+                LintSeverity::Warning,
+                format!("entrypoint executable {executable} does not exist"),
+            );
+        }
+    }
+
+    let base_name = opt
+        .annotations
+        .get(ANNOTATION_BASE_NAME)
+        .or_else(|| labels.get(ANNOTATION_BASE_NAME));
+    if let Some(name) = base_name.filter(|name| is_latest_reference(name)) {
+        add_issue(
+            CHECK_LATEST_BASE_IMAGE,
+            LintSeverity::Warning,
+            format!("base image {name} should be pinned to a version tag or digest"),
+        );
+    }
+
+    let secret_envs = find_env_secrets(&envs);
+    if !secret_envs.is_empty() {
+        add_issue(
+            CHECK_ENV_SECRET,
+            LintSeverity::Warning,
+            format!("Env must not contain secrets: {}", secret_envs.join(", ")),
+        );
+    }
+
+    let missing_labels: Vec<String> = OCI_STANDARD_LABELS
+        .iter()
+        .filter(|item| !labels.contains_key(**item))
+        .map(|item| item.to_string())
+        .collect();
+    if !missing_labels.is_empty() {
+        add_issue(
+            CHECK_MISSING_OCI_LABELS,
+            LintSeverity::Warning,
+            format!(
+                "OCI standard labels are missing: {}",
+                missing_labels.join(", ")
+            ),
+        );
+    }
+
+    let remote_urls = find_remote_add(opt.history);
+    if !remote_urls.is_empty() {
+        add_issue(
+            CHECK_ADD_REMOTE_URL,
+            LintSeverity::Warning,
+            format!(
+                "ADD with remote URL should be replaced by a checksum verified download: {}",
+                remote_urls.join(", ")
+            ),
+        );
+    }
+    issues
+}
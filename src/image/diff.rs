@@ -0,0 +1,311 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+use super::{DockerAnalyzeResult, ImageFileInfo, Op};
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageDiffFile {
+    pub path: String,
+    // 新增、删除或修改
+    pub op: Op,
+    // 对比前的文件大小（新增则为0）
+    pub before_size: u64,
+    // 对比后的文件大小（删除则为0）
+    pub after_size: u64,
+    // 大小变化
+    pub size_delta: i64,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageFileMetadata {
+    pub mode: String,
+    pub uid: u64,
+    pub gid: u64,
+    pub mtime: i64,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageMetadataDiff {
+    pub path: String,
+    // 不一致的元数据：mode、uid、gid或mtime
+    pub fields: Vec<String>,
+    pub before: ImageFileMetadata,
+    pub after: ImageFileMetadata,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageDiffLayer {
+    pub digest: String,
+    // 在对比前镜像中的层
+    pub before_layer_index: usize,
+    // 在对比后镜像中的层
+    pub after_layer_index: usize,
+}
+
+#[derive(Default, Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageDiffSummary {
+    // 镜像大小
+    pub before_size: u64,
+    pub after_size: u64,
+    pub size_delta: i64,
+    // 镜像解压大小
+    pub before_total_size: u64,
+    pub after_total_size: u64,
+    pub total_size_delta: i64,
+    // 镜像效率分数
+    pub before_score: u64,
+    pub after_score: u64,
+    pub score_delta: i64,
+    // 浪费空间
+    pub before_wasted_size: u64,
+    pub after_wasted_size: u64,
+    pub wasted_size_delta: i64,
+    // 共享层的大小（无需重新下载）
+    pub shared_size: u64,
+    // 新增、删除与修改的文件数
+    pub added_count: usize,
+    pub removed_count: usize,
+    pub modified_count: usize,
+    // 内容一致仅元数据不同的文件数
+    pub metadata_count: usize,
+}
+
+#[derive(Default, Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageDiffResult {
+    // 对比前的镜像
+    pub before: String,
+    // 对比后的镜像
+    pub after: String,
+    // 两个镜像均有的层（按digest匹配）
+    pub shared_layer_list: Vec<ImageDiffLayer>,
+    // 最终文件系统的差异
+    pub file_list: Vec<ImageDiffFile>,
+    // 内容一致仅元数据不同的文件
+    pub metadata_file_list: Vec<ImageMetadataDiff>,
+    pub summary: ImageDiffSummary,
+}
+
+impl ImageDiffResult {
+    // 两个镜像的层、文件内容与元数据是否完全一致
+    pub fn is_identical(&self) -> bool {
+        self.summary.before_size == self.summary.after_size
+            && self.summary.shared_size == self.summary.before_size
+            && self.file_list.is_empty()
+            && self.metadata_file_list.is_empty()
+    }
+}
+
+fn delta(before: u64, after: u64) -> i64 {
+    after as i64 - before as i64
+}
+
+fn is_dir(info: &ImageFileInfo) -> bool {
+    info.mode.starts_with('d')
+}
+
+// 文件内容是否有变化（元数据的变化由diff_metadata对比），有digest时以digest为准
+fn is_modified(before: &ImageFileInfo, after: &ImageFileInfo) -> bool {
+    if before.size != after.size || before.link != after.link {
+        return true;
+    }
+    match (&before.digest, &after.digest) {
+        (Some(before_digest), Some(after_digest)) => before_digest != after_digest,
+        _ => false,
+    }
+}
+
+// 内容一致时，对比文件的元数据
+fn diff_metadata(
+    path: &str,
+    before: &ImageFileInfo,
+    after: &ImageFileInfo,
+) -> Option<ImageMetadataDiff> {
+    if is_modified(before, after) {
+        return None;
+    }
+    let mut fields = vec![];
+    if before.mode != after.mode {
+        fields.push("mode".to_string());
+    }
+    if before.uid != after.uid {
+        fields.push("uid".to_string());
+    }
+    if before.gid != after.gid {
+        fields.push("gid".to_string());
+    }
+    if before.mtime != after.mtime {
+        fields.push("mtime".to_string());
+    }
+    if fields.is_empty() {
+        return None;
+    }
+    let metadata = |info: &ImageFileInfo| ImageFileMetadata {
+        mode: info.mode.clone(),
+        uid: info.uid,
+        gid: info.gid,
+        mtime: info.mtime,
+    };
+    Some(ImageMetadataDiff {
+        path: path.to_string(),
+        fields,
+        before: metadata(before),
+        after: metadata(after),
+    })
+}
+
+// 对比两个镜像的分析结果
+pub fn diff_image(before: &DockerAnalyzeResult, after: &DockerAnalyzeResult) -> ImageDiffResult {
+    // 按digest匹配共享的层
+    let mut shared_layer_list = vec![];
+    let mut shared_digests = HashSet::new();
+    let mut shared_size = 0;
+    for (before_index, layer) in before.layers.iter().enumerate() {
+        if layer.empty || layer.digest.is_empty() || shared_digests.contains(&layer.digest) {
+            continue;
+        }
+        if let Some(after_index) = after
+            .layers
+            .iter()
+            .position(|item| !item.empty && item.digest == layer.digest)
+        {
+            shared_digests.insert(layer.digest.clone());
+            shared_size += layer.size;
+            shared_layer_list.push(ImageDiffLayer {
+                digest: layer.digest.clone(),
+                before_layer_index: before_index,
+                after_layer_index: after_index,
+            });
+        }
+    }
+
+    // 目录的变化由其下文件体现，因此忽略
+    let before_files: BTreeMap<&str, &ImageFileInfo> = before
+        .merged_file_list
+        .iter()
+        .filter(|file| !is_dir(&file.info))
+        .map(|file| (file.info.path.as_str(), &file.info))
+        .collect();
+    let after_files: BTreeMap<&str, &ImageFileInfo> = after
+        .merged_file_list
+        .iter()
+        .filter(|file| !is_dir(&file.info))
+        .map(|file| (file.info.path.as_str(), &file.info))
+        .collect();
+
+    let mut file_list = vec![];
+    let mut metadata_file_list = vec![];
+    for (path, info) in before_files.iter() {
+        if let Some(item) = after_files
+            .get(path)
+            .and_then(|after_info| diff_metadata(path, info, after_info))
+        {
+            metadata_file_list.push(item);
+        }
+        match after_files.get(path) {
+            Some(after_info) if is_modified(info, after_info) => {
+                file_list.push(ImageDiffFile {
+                    path: path.to_string(),
+                    op: Op::Modified,
+                    before_size: info.size,
+                    after_size: after_info.size,
+                    size_delta: delta(info.size, after_info.size),
+                });
+            }
+            Some(_) => {}
+            None => {
+                file_list.push(ImageDiffFile {
+                    path: path.to_string(),
+                    op: Op::Removed,
+                    before_size: info.size,
+                    after_size: 0,
+                    size_delta: delta(info.size, 0),
+                });
+            }
+        }
+    }
+    for (path, info) in after_files.iter() {
+        if before_files.contains_key(path) {
+            continue;
+        }
+        file_list.push(ImageDiffFile {
+            path: path.to_string(),
+            op: Op::Added,
+            before_size: 0,
+            after_size: info.size,
+            size_delta: delta(0, info.size),
+        });
+    }
+    file_list.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let before_summary = before.summary();
+    let after_summary = after.summary();
+    let count = |op: Op| file_list.iter().filter(|item| item.op == op).count();
+    let summary = ImageDiffSummary {
+        before_size: before.size,
+        after_size: after.size,
+        size_delta: delta(before.size, after.size),
+        before_total_size: before.total_size,
+        after_total_size: after.total_size,
+        total_size_delta: delta(before.total_size, after.total_size),
+        before_score: before_summary.score,
+        after_score: after_summary.score,
+        score_delta: delta(before_summary.score, after_summary.score),
+        before_wasted_size: before_summary.wasted_size,
+        after_wasted_size: after_summary.wasted_size,
+        wasted_size_delta: delta(before_summary.wasted_size, after_summary.wasted_size),
+        shared_size,
+        added_count: count(Op::Added),
+        removed_count: count(Op::Removed),
+        modified_count: count(Op::Modified),
+        metadata_count: metadata_file_list.len(),
+    };
+    ImageDiffResult {
+        before: before.name.clone(),
+        after: after.name.clone(),
+        shared_layer_list,
+        file_list,
+        metadata_file_list,
+        summary,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::diff_image;
+    use crate::image::{DockerAnalyzeResult, ImageFileInfo, ImageMergedFile};
+
+    fn new_result(mode: &str) -> DockerAnalyzeResult {
+        DockerAnalyzeResult {
+            merged_file_list: vec![ImageMergedFile {
+                layer_index: 0,
+                info: ImageFileInfo {
+                    path: "usr/local/bin/app".to_string(),
+                    size: 1024,
+                    mode: mode.to_string(),
+                    digest: Some("sha256:app".to_string()),
+                    ..Default::default()
+                },
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_diff_chmod_only() {
+        let result = diff_image(&new_result("-rw-r--r--"), &new_result("-rwxr-xr-x"));
+        // 仅权限变化的文件不属于修改的文件
+        assert!(result.file_list.is_empty());
+        assert_eq!(0, result.summary.modified_count);
+        assert_eq!(1, result.summary.metadata_count);
+        assert_eq!(
+            vec!["mode".to_string()],
+            result.metadata_file_list[0].fields
+        );
+    }
+}
@@ -1,5 +1,5 @@
-use crate::config::must_load_config;
-use crate::{task_local::*, tl_info};
+use crate::config::{get_allow_foreign_layer, get_config_checks, get_lint_rules, must_load_config};
+use crate::{task_local::*, tl_info, tl_warn};
 use chrono::{DateTime, Utc};
 use http::StatusCode;
 use lru::LruCache;
@@ -11,15 +11,36 @@ use serde_json::Value;
 use snafu::{ResultExt, Snafu};
 use std::io::Write;
 use std::process::{Command, Stdio};
-use std::{collections::HashMap, num::NonZeroUsize, str::FromStr, sync::Mutex, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    num::NonZeroUsize,
+    str::FromStr,
+    sync::Mutex,
+    time::Duration,
+};
 use substring::Substring;
+use tracing::warn;
 
-use super::{get_file_content_from_tar, get_file_size_from_tar, get_files_from_layer};
 use super::{
+    add_file_type_size, audit_permissions, audit_reproducibility, build_sbom, check_image_config,
+    find_duplicate_files, find_missing_libraries, find_package_caches, find_vulnerabilities,
+    get_account_ids, get_osv_database, get_secret_scanner,
     layer::ImageLayerInfo,
-    oci_image::{ImageFileSummary, ImageManifestLayer},
-    FileTreeItem, ImageConfig, ImageIndex, ImageLayer, ImageManifest, ImageManifestConfig, Op,
-    MEDIA_TYPE_DOCKER_SCHEMA2_MANIFEST, MEDIA_TYPE_IMAGE_INDEX, MEDIA_TYPE_MANIFEST_LIST,
+    lint_image,
+    oci_image::{convert_merged_files_to_file_tree, ImageFileSummary, ImageManifestLayer},
+    summarize_zstd_savings, ConfigCheckOption, DuplicateFileGroup, ElfBinaryInfo, FileTreeItem,
+    FileTypeSize, ImageConfig, ImageExtraInfo, ImageIndex, ImageLayer, ImageManifest,
+    ImageManifestConfig, ImageSbom, LanguageComponent, MergedFileSystem, Op, SecretFinding,
+    VulnerabilityFinding, ZstdSavings, MEDIA_TYPE_DOCKER_SCHEMA2_MANIFEST, MEDIA_TYPE_IMAGE_INDEX,
+    MEDIA_TYPE_MANIFEST_LIST,
+};
+use super::{
+    find_ghost_files, is_encrypted_layer, is_foreign_layer, GhostFileInfo, ImageHistory,
+    ImageMergedFile, LintIssue, PackageCacheInfo, PermissionAuditOption, PermissionFinding,
+    ReproducibilityOption, ReproducibilityReport, ANNOTATION_BASE_DIGEST, ANNOTATION_BASE_NAME,
+};
+use super::{
+    get_file_content_from_tar, get_file_size_from_tar, get_files_from_layer, get_sha256_digest,
 };
 use crate::{
     error::HTTPError,
@@ -50,6 +71,17 @@ pub enum Error {
         code: String,
         url: String,
     },
+    #[snafu(display(
+        "Blob {} digest mismatch, expected: {}, actual: {}",
+        url,
+        expected,
+        actual
+    ))]
+    DigestMismatch {
+        url: String,
+        expected: String,
+        actual: String,
+    },
     #[snafu(display("{message}"))]
     Whatever { message: String },
 }
@@ -80,6 +112,10 @@ pub struct ImageInfo {
     pub tag: String,
     // 镜像架构
     pub arch: String,
+    // 已知的基础镜像
+    pub base: String,
+    // 命令行指定的SOURCE_DATE_EPOCH
+    pub source_date_epoch: Option<i64>,
 }
 
 static FILE_PROTOCOL: &str = "file://";
@@ -144,6 +180,7 @@ pub fn parse_image_info(image: &str) -> ImageInfo {
         name,
         tag,
         arch,
+        ..Default::default()
     }
 }
 
@@ -201,6 +238,37 @@ pub struct BigModifiedFileInfo {
     pub digest: String,
 }
 
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffIdMismatchInfo {
+    pub layer_index: usize,
+    // rootfs.diff_ids中的值
+    pub expected: String,
+    // 解压后数据的digest
+    pub actual: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaseImageInfo {
+    // 基础镜像名称
+    pub name: String,
+    // 基础镜像的digest
+    pub digest: String,
+    // 来源：annotation或cli
+    pub source: String,
+    // 属于基础镜像的layer数（包括空layer）
+    pub layer_count: usize,
+    // 基础镜像的大小
+    pub size: u64,
+    // 基础镜像的解压大小
+    pub total_size: u64,
+    // 应用层的大小
+    pub app_size: u64,
+    // 应用层的解压大小
+    pub app_total_size: u64,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DockerAnalyzeResult {
@@ -224,10 +292,52 @@ pub struct DockerAnalyzeResult {
     pub total_size: u64,
     // 镜像分层对应的文件树
     pub file_tree_list: Vec<Vec<FileTreeItem>>,
+    // 叠加所有层后的文件树（最终文件系统）
+    pub merged_file_tree: Vec<FileTreeItem>,
+    // 每层的文件，用于按需生成叠加至该层后的文件树（不输出）
+    #[serde(skip)]
+    pub layer_file_list: Vec<Vec<ImageFileInfo>>,
+    // 最终文件系统的所有文件（用于镜像对比，不输出）
+    #[serde(skip)]
+    pub merged_file_list: Vec<ImageMergedFile>,
     // 镜像删除与更新文件汇总
     pub file_summary_list: Vec<ImageFileSummary>,
     // 本次镜像变化的大文件
     pub big_modified_file_list: Vec<BigModifiedFileInfo>,
+    // 最终文件系统中内容相同的文件
+    pub duplicate_file_list: Vec<DuplicateFileGroup>,
+    // 添加后又在后续层删除的文件
+    pub ghost_file_list: Vec<GhostFileInfo>,
+    // 是否有layer解析失败（分析结果不完整）
+    pub incomplete: bool,
+    // 分析过程中的告警信息
+    pub warning_list: Vec<String>,
+    // 解压后数据与diff_id不一致的layer（镜像可能被篡改）
+    pub diff_id_mismatch_list: Vec<DiffIdMismatchInfo>,
+    // 基础镜像（未能识别则为空）
+    pub base_image: Option<BaseImageInfo>,
+    // 最终文件系统中包管理工具的缓存
+    pub package_cache_list: Vec<PackageCacheInfo>,
+    // 不符合检测规则的问题
+    pub lint_issue_list: Vec<LintIssue>,
+    // 文件与镜像配置中的敏感信息（需启用检测）
+    pub secret_list: Vec<SecretFinding>,
+    // 根据系统包数据库生成的sbom
+    pub sbom: ImageSbom,
+    // 最终文件系统中语言生态的组件
+    pub component_list: Vec<LanguageComponent>,
+    // 匹配osv数据库的漏洞（需配置osv数据库）
+    pub vulnerability_list: Vec<VulnerabilityFinding>,
+    // 最终文件系统中的elf二进制文件
+    pub elf_binary_list: Vec<ElfBinaryInfo>,
+    // 所有layer按文件类型汇总的大小
+    pub file_type_list: Vec<FileTypeSize>,
+    // 最终文件系统中权限与属主的问题
+    pub permission_finding_list: Vec<PermissionFinding>,
+    // 镜像的运行配置（entrypoint、cmd、端口等）
+    pub image_config: ImageExtraInfo,
+    // 影响可重现构建的修改时间、属主、tar header与build id
+    pub reproducibility: ReproducibilityReport,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize)]
@@ -237,12 +347,46 @@ pub struct ImageFileWastedSummary {
     pub count: u32,
 }
 
+#[derive(Default, Debug, Clone, Serialize)]
+pub struct PackageCacheSummary {
+    pub ecosystem: String,
+    pub size: u64,
+    // 添加缓存的layer
+    pub layers: Vec<usize>,
+}
+
+#[derive(Default, Debug, Clone, Serialize)]
+pub struct VulnerabilitySummary {
+    pub layer_index: usize,
+    pub count: usize,
+}
+
 #[derive(Default, Debug, Clone, Serialize)]
 pub struct DockerAnalyzeSummary {
     pub wasted_list: Vec<ImageFileWastedSummary>,
     pub wasted_size: u64,
     pub wasted_percent: f64,
     pub score: u64,
+    // 重复文件可节省的空间
+    pub duplicate_size: u64,
+    // 添加后又删除的文件所占用的空间
+    pub ghost_size: u64,
+    // 包管理工具缓存所占用的空间
+    pub package_cache_size: u64,
+    // 按包管理工具汇总的缓存
+    pub package_cache_list: Vec<PackageCacheSummary>,
+    // 漏洞总数
+    pub vulnerability_count: usize,
+    // 按layer汇总的漏洞数
+    pub vulnerability_list: Vec<VulnerabilitySummary>,
+    // strip二进制文件预计可节省的空间
+    pub strip_savings: u64,
+    // 各压缩级别以zstd压缩layer后拉取时预计可节省的空间
+    pub zstd_savings_list: Vec<ZstdSavings>,
+    // 未校验diff id的layer
+    pub unverified_layers: Vec<usize>,
+    // 未分析elf与语言组件的layer
+    pub content_skipped_layers: Vec<usize>,
 }
 
 impl DockerAnalyzeResult {
@@ -268,18 +412,92 @@ impl DockerAnalyzeResult {
                 });
             }
         }
+        // 保持原有写法，新版本clippy新增的lint
+        #[allow(clippy::unnecessary_sort_by)]
         wasted_list.sort_by(|a, b| b.total_size.cmp(&a.total_size));
 
-        let mut score = 100 - wasted_size * 100 / self.total_size;
+        // 所有layer均解析失败时解压大小为0
+        let total_size = self.total_size.max(1);
+        let mut score = 100 - (wasted_size * 100 / total_size).min(100);
         // 有浪费空间，则分数-1
         if wasted_size != 0 {
-            score -= 1;
+            score = score.saturating_sub(1);
+        }
+        let duplicate_size = self
+            .duplicate_file_list
+            .iter()
+            .map(|item| item.wasted_size)
+            .sum();
+        let ghost_size = self.ghost_file_list.iter().map(|item| item.size).sum();
+        let mut package_cache_list: Vec<PackageCacheSummary> = vec![];
+        for cache in self.package_cache_list.iter() {
+            let index = if let Some(index) = package_cache_list
+                .iter()
+                .position(|item| item.ecosystem == cache.ecosystem)
+            {
+                index
+            } else {
+                package_cache_list.push(PackageCacheSummary {
+                    ecosystem: cache.ecosystem.clone(),
+                    ..Default::default()
+                });
+                package_cache_list.len() - 1
+            };
+            let summary = &mut package_cache_list[index];
+            summary.size += cache.size;
+            if !summary.layers.contains(&cache.layer_index) {
+                summary.layers.push(cache.layer_index);
+                summary.layers.sort();
+            }
+        }
+        package_cache_list.sort_by_key(|item| std::cmp::Reverse(item.size));
+        let package_cache_size = package_cache_list.iter().map(|item| item.size).sum();
+        let mut vulnerability_list: Vec<VulnerabilitySummary> = vec![];
+        for finding in self.vulnerability_list.iter() {
+            if let Some(summary) = vulnerability_list
+                .iter_mut()
+                .find(|item| item.layer_index == finding.layer_index)
+            {
+                summary.count += 1;
+            } else {
+                vulnerability_list.push(VulnerabilitySummary {
+                    layer_index: finding.layer_index,
+                    count: 1,
+                });
+            }
         }
+        vulnerability_list.sort_by_key(|item| item.layer_index);
         DockerAnalyzeSummary {
             wasted_list,
             wasted_size,
-            wasted_percent: (wasted_size as f64) / (self.total_size as f64),
+            wasted_percent: (wasted_size as f64) / (total_size as f64),
             score,
+            duplicate_size,
+            ghost_size,
+            package_cache_size,
+            package_cache_list,
+            vulnerability_count: self.vulnerability_list.len(),
+            vulnerability_list,
+            strip_savings: self
+                .elf_binary_list
+                .iter()
+                .map(|item| item.strip_savings())
+                .sum(),
+            zstd_savings_list: summarize_zstd_savings(&self.layers),
+            unverified_layers: self
+                .layers
+                .iter()
+                .enumerate()
+                .filter(|(_, layer)| layer.diff_id_unverified.is_some())
+                .map(|(index, _)| index)
+                .collect(),
+            content_skipped_layers: self
+                .layers
+                .iter()
+                .enumerate()
+                .filter(|(_, layer)| layer.content_skipped.is_some())
+                .map(|(index, _)| index)
+                .collect(),
         }
     }
 }
@@ -417,6 +635,175 @@ fn add_to_file_summary(
     }
 }
 
+// 对齐layer与history，manifest中的layer与rootfs.diff_ids按位置一一对应
+// 返回每条history、其对应的layer（空操作为None）以及该layer的diff id，无history的layer生成对应的记录
+fn align_layer_history(
+    config: &ImageConfig,
+    manifest_layer_count: usize,
+    warning_list: &mut Vec<String>,
+) -> Vec<(ImageHistory, Option<usize>, Option<String>)> {
+    let diff_ids = &config.rootfs.diff_ids;
+    // 数量不一致时保留所有的layer，缺少diff id的layer无法校验
+    if diff_ids.len() != manifest_layer_count {
+        warning_list.push(format!(
+            "rootfs.diff_ids count({}) is not equal to manifest layers count({manifest_layer_count})",
+            diff_ids.len()
+        ));
+    }
+    let history_count = config
+        .history
+        .iter()
+        .filter(|item| !item.empty_layer.unwrap_or_default())
+        .count();
+    if history_count != manifest_layer_count {
+        warning_list.push(format!(
+            "non-empty history count({history_count}) is not equal to layers count({manifest_layer_count})"
+        ));
+    }
+    let mut index = 0;
+    let mut aligned_history_list = vec![];
+    for history in config.history.iter() {
+        // 多余的history视为空操作
+        if history.empty_layer.unwrap_or_default() || index >= manifest_layer_count {
+            aligned_history_list.push((history.clone(), None, None));
+            continue;
+        }
+        aligned_history_list.push((history.clone(), Some(index), diff_ids.get(index).cloned()));
+        index += 1;
+    }
+    // 无history的layer
+    for i in index..manifest_layer_count {
+        aligned_history_list.push((
+            ImageHistory {
+                created_by: Some("(no history)".to_string()),
+                ..Default::default()
+            },
+            Some(i),
+            diff_ids.get(i).cloned(),
+        ));
+    }
+    aligned_history_list
+}
+
+// 两个列表相同前缀的数量
+fn common_prefix_count(a: &[String], b: &[String]) -> usize {
+    a.iter().zip(b.iter()).take_while(|(x, y)| x == y).count()
+}
+
+// 解析基础镜像的信息，名称中包含digest或指定了digest时以digest获取
+fn parse_base_image_info(name: &str, digest: &str, arch: &str) -> ImageInfo {
+    let (name, digest) = name.split_once('@').unwrap_or((name, digest));
+    // docker.io为docker hub的别名
+    let mut image_info = parse_image_info(name.strip_prefix("docker.io/").unwrap_or(name));
+    let is_local =
+        image_info.registry == REGISTRY_LOCAL_FILE || image_info.registry == REGISTRY_LOCAL_DOCKER;
+    if !digest.is_empty() && !is_local {
+        image_info.tag = digest.to_string();
+    }
+    if image_info.arch.is_empty() {
+        image_info.arch = arch.to_string();
+    }
+    image_info
+}
+
+// 获取镜像的layer digest与rootfs.diff_ids
+async fn get_image_layer_ids(image_info: ImageInfo) -> Result<(Vec<String>, Vec<String>)> {
+    let mut _tmpfile = None;
+    let (c, mut params) = if image_info.registry == REGISTRY_LOCAL_DOCKER {
+        let buf = get_buf_from_local_docker(&image_info.name)?;
+        let mut tmpfile = tempfile::Builder::new().tempfile().context(IOSnafu {})?;
+        tmpfile.write_all(&buf).context(IOSnafu {})?;
+        tmpfile.flush().context(IOSnafu {})?;
+        let filename = tmpfile.path().to_string_lossy().to_string();
+        // 读取完成前需保留临时文件
+        _tmpfile = Some(tmpfile);
+        (
+            DockerClient::new(REGISTRY_LOCAL_FILE),
+            DockerImageParams {
+                img: filename,
+                ..Default::default()
+            },
+        )
+    } else {
+        (
+            DockerClient::new(&image_info.registry),
+            DockerImageParams {
+                user: image_info.user,
+                img: image_info.name,
+                tag: image_info.tag,
+                arch: image_info.arch,
+                ..Default::default()
+            },
+        )
+    };
+    params.token = c.get_auth_token(&params).await?;
+    let manifest = c.get_manifest(&params).await?;
+    let config = c.get_image_config(&params).await?;
+    let digests = manifest
+        .layers
+        .into_iter()
+        .map(|item| item.digest)
+        .collect();
+    Ok((digests, config.rootfs.diff_ids))
+}
+
+// 识别基础镜像，优先使用命令行指定的基础镜像，其次为manifest中的annotation
+// 返回基础镜像的信息以及匹配的layer数（manifest中的layer）
+async fn detect_base_image(
+    params: &DockerImageParams,
+    manifest: &ImageManifest,
+    config: &ImageConfig,
+) -> Result<Option<(BaseImageInfo, usize)>> {
+    let annotations = manifest.annotations.clone().unwrap_or_default();
+    let (name, digest, source) = if !params.base.is_empty() {
+        (params.base.clone(), "".to_string(), "cli")
+    } else if let Some(digest) = annotations.get(ANNOTATION_BASE_DIGEST) {
+        let Some(name) = annotations.get(ANNOTATION_BASE_NAME) else {
+            return Err(Error::Whatever {
+                message: format!("base image name of {digest} is missing"),
+            });
+        };
+        (name.clone(), digest.clone(), "annotation")
+    } else if let Some(name) = annotations.get(ANNOTATION_BASE_NAME) {
+        (name.clone(), "".to_string(), "annotation")
+    } else {
+        return Ok(None);
+    };
+    tl_info!(name = name, digest = digest, "getting base image");
+    let (base_digests, base_diff_ids) =
+        get_image_layer_ids(parse_base_image_info(&name, &digest, &params.arch)).await?;
+    // 优先以diff_ids匹配（不受压缩方式影响），其次为layer的digest
+    let digests: Vec<String> = manifest
+        .layers
+        .iter()
+        .map(|item| item.digest.clone())
+        .collect();
+    let count = common_prefix_count(&config.rootfs.diff_ids, &base_diff_ids)
+        .max(common_prefix_count(&digests, &base_digests));
+    if count == 0 {
+        return Err(Error::Whatever {
+            message: format!("base image {name} does not match any layer"),
+        });
+    }
+    let base_layer_count = base_digests.len().max(base_diff_ids.len());
+    if count < base_layer_count {
+        return Err(Error::Whatever {
+            message: format!(
+                "only {count} of {base_layer_count} layers of base image {name} are matched"
+            ),
+        });
+    }
+    Ok(Some((
+        BaseImageInfo {
+            name,
+            digest,
+            source: source.to_string(),
+            ..Default::default()
+        },
+        count,
+    )))
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LocalManifest {
@@ -426,6 +813,9 @@ pub struct LocalManifest {
     pub repo_tags: Vec<String>,
     #[serde(rename = "Layers")]
     pub layers: Vec<String>,
+    // 外部layer的信息，key为diff id
+    #[serde(rename = "LayerSources")]
+    pub layer_sources: Option<HashMap<String, ImageManifestLayer>>,
 }
 
 impl From<LocalManifest> for ImageManifest {
@@ -447,6 +837,7 @@ impl From<LocalManifest> for ImageManifest {
                 ..Default::default()
             },
             layers,
+            ..Default::default()
         }
     }
 }
@@ -463,6 +854,10 @@ pub struct DockerImageParams {
     pub token: String,
     // 镜像架构
     pub arch: String,
+    // 已知的基础镜像
+    pub base: String,
+    // 命令行指定的SOURCE_DATE_EPOCH
+    pub source_date_epoch: Option<i64>,
 }
 
 fn get_buf_from_local_docker(image: &str) -> Result<Vec<u8>> {
@@ -561,8 +956,33 @@ impl DockerClient {
         let token = &params.token;
         if self.is_local() {
             let local_manifest = self.get_local_manifest(img).await?;
+            let layer_sources = local_manifest.layer_sources.clone().unwrap_or_default();
+            let config_file = local_manifest.config.clone();
             let mut image_manifest: ImageManifest = local_manifest.into();
-            for layer in image_manifest.layers.iter_mut() {
+            // 外部layer不在tar中，根据LayerSources替换为其原始信息
+            if !layer_sources.is_empty() {
+                let data = get_file_content_from_tar(img, &config_file)
+                    .await
+                    .context(LayerSnafu {})?;
+                let config: ImageConfig =
+                    serde_json::from_slice(&data).context(SerdeJsonSnafu {
+                        category: "get_local_config",
+                    })?;
+                for (layer, diff_id) in image_manifest
+                    .layers
+                    .iter_mut()
+                    .zip(config.rootfs.diff_ids.iter())
+                {
+                    if let Some(source) = layer_sources.get(diff_id) {
+                        *layer = source.clone();
+                    }
+                }
+            }
+            for layer in image_manifest
+                .layers
+                .iter_mut()
+                .filter(|layer| !is_foreign_layer(&layer.media_type))
+            {
                 let size = get_file_size_from_tar(img, &layer.digest)
                     .await
                     .context(LayerSnafu {})?;
@@ -671,13 +1091,82 @@ impl DockerClient {
         tl_info!(url = url, "got blob");
         Ok(resp.to_vec())
     }
+    // 获取外部layer的blob，仅在配置允许时从其urls中下载
+    // 下载的数据需校验digest，避免写入错误的缓存
+    async fn get_foreign_blob(&self, layer: &ImageManifestLayer) -> Result<Option<Vec<u8>>> {
+        if let Ok(data) = get_blob_from_file(&layer.digest).await {
+            if get_sha256_digest(&data) == layer.digest {
+                return Ok(Some(data));
+            }
+        }
+        if !get_allow_foreign_layer() {
+            return Ok(None);
+        }
+        let urls = layer.urls.clone().unwrap_or_default();
+        let mut err = None;
+        for url in urls {
+            tl_info!(url = url, "getting foreign blob");
+            let resp = match self.get_bytes(url.clone(), HashMap::new()).await {
+                Ok(resp) => resp,
+                Err(e) => {
+                    err = Some(e);
+                    continue;
+                }
+            };
+            let actual = get_sha256_digest(&resp);
+            if actual != layer.digest {
+                tl_warn!(
+                    url = url,
+                    expected = layer.digest,
+                    actual,
+                    "foreign blob digest mismatch"
+                );
+                err = Some(Error::DigestMismatch {
+                    url,
+                    expected: layer.digest.clone(),
+                    actual,
+                });
+                continue;
+            }
+            // 出错忽略
+            let _ = save_blob_to_file(&layer.digest, &resp).await;
+            tl_info!(url = url, "got foreign blob");
+            return Ok(Some(resp.to_vec()));
+        }
+        if let Some(err) = err {
+            return Err(err);
+        }
+        Ok(None)
+    }
     async fn get_layer_files(
         &self,
         params: &DockerImageParams,
         layer: ImageManifestLayer,
     ) -> Result<ImageLayerInfo> {
         let img = &params.img;
-        let buf = if self.is_local() {
+        // 加密的layer无法解析，仅记录声明的大小
+        if is_encrypted_layer(&layer.media_type) {
+            tl_info!(digest = layer.digest, "skip encrypted layer");
+            return Ok(ImageLayerInfo {
+                size: layer.size,
+                unsupported: Some("encrypted layer".to_string()),
+                ..Default::default()
+            });
+        }
+        // 外部layer优先于本地tar判断，docker save的tar中不包括外部layer
+        let buf = if is_foreign_layer(&layer.media_type) {
+            match self.get_foreign_blob(&layer).await? {
+                Some(buf) => buf,
+                None => {
+                    tl_info!(digest = layer.digest, "skip foreign layer");
+                    return Ok(ImageLayerInfo {
+                        size: layer.size,
+                        unsupported: Some("foreign layer".to_string()),
+                        ..Default::default()
+                    });
+                }
+            }
+        } else if self.is_local() {
             get_file_content_from_tar(img, &layer.digest)
                 .await
                 .context(LayerSnafu {})?
@@ -685,7 +1174,8 @@ impl DockerClient {
             self.get_blob(params, &layer.digest).await?
         };
 
-        let info = get_files_from_layer(&buf, &layer.media_type)
+        let annotations = layer.annotations.unwrap_or_default();
+        let info = get_files_from_layer(&buf, &layer.media_type, &annotations)
             .await
             .context(LayerSnafu {})?;
         Ok(info)
@@ -710,14 +1200,26 @@ impl DockerClient {
                 TRACE_ID
                     .scope(trace_id, async {
                         let mut handles = Vec::with_capacity(layers.len());
-                        for layer in layers {
-                            handles.push(s.get_layer_files(&params, layer));
+                        for layer in layers.iter() {
+                            handles.push(s.get_layer_files(&params, layer.clone()));
                         }
 
                         let arr = futures::future::join_all(handles).await;
                         let mut info_list = vec![];
-                        for result in arr {
-                            let info = result?;
+                        // 单个layer解析失败不影响其它layer，记录出错信息
+                        for (layer, result) in layers.iter().zip(arr) {
+                            let info = result.unwrap_or_else(|err| {
+                                tl_warn!(
+                                    digest = layer.digest,
+                                    err = err.to_string(),
+                                    "get layer files fail"
+                                );
+                                ImageLayerInfo {
+                                    size: layer.size,
+                                    error: Some(err.to_string()),
+                                    ..Default::default()
+                                }
+                            });
                             info_list.push(info);
                         }
                         Ok::<Vec<ImageLayerInfo>, Error>(info_list)
@@ -790,8 +1292,10 @@ impl DockerClient {
         let mut layers = vec![];
         // let mut layer_infos = vec![];
         let mut file_tree_list: Vec<Vec<FileTreeItem>> = vec![];
-        let mut index = 0;
+        let mut layer_file_list: Vec<Vec<ImageFileInfo>> = vec![];
         let mut file_summary_list = vec![];
+        let mut warning_list = vec![];
+        let mut diff_id_mismatch_list = vec![];
         tl_info!(user = user, img = img, tag = tag, "analyzing image",);
 
         let mut image_size = 0;
@@ -806,15 +1310,54 @@ impl DockerClient {
             }
         }
         let mut big_modified_file_list = vec![];
-        for (layer_index, history) in config.history.iter().enumerate() {
-            let is_new = if let Ok(value) = DateTime::parse_from_rfc3339(&history.created) {
+        let mut merged_file_system = MergedFileSystem::default();
+        let mut removed_file_list = vec![];
+        let mut secret_list = vec![];
+        let mut package_db_list = vec![];
+        let mut component_list = vec![];
+        let mut elf_binary_list = vec![];
+        let mut ld_config_list = vec![];
+        let mut file_type_list = vec![];
+        // 按层叠加后的目录
+        let mut merged_dir_system = MergedFileSystem::default();
+        let mut account_list = vec![];
+        let mut tar_header_list = vec![];
+        let aligned_history_list =
+            align_layer_history(&config, manifest.layers.len(), &mut warning_list);
+        let mut base_image = None;
+        // 基础镜像最后一层对应的history
+        let mut base_boundary = None;
+        match detect_base_image(params, &manifest, &config).await {
+            Ok(Some((info, count))) => {
+                base_image = Some(info);
+                base_boundary = aligned_history_list
+                    .iter()
+                    .position(|(_, index, _)| *index == Some(count - 1));
+            }
+            Ok(None) => {}
+            Err(err) => warning_list.push(format!("detect base image fail, {err}")),
+        }
+        for warning in warning_list.iter() {
+            tl_warn!(user = user, img = img, tag = tag, warning);
+        }
+        for (layer_index, (history, manifest_index, layer_diff_id)) in
+            aligned_history_list.iter().enumerate()
+        {
+            let base = base_boundary.is_some_and(|boundary| layer_index <= boundary);
+            // 识别到基础镜像时，非基础镜像的layer均为新的layer
+            let is_new = if base_image.is_some() {
+                !base
+            } else if let Ok(value) = DateTime::parse_from_rfc3339(&history.created) {
                 // 如果5分钟内
                 image_created - value.timestamp() < 300
             } else {
                 false
             };
-            let empty = history.empty_layer.unwrap_or_default();
+            let empty = manifest_index.is_none();
             let mut digest = "".to_string();
+            let mut diff_id = "".to_string();
+            let mut diff_id_verified = None;
+            let mut diff_id_unverified = None;
             let mut info = &ImageLayerInfo {
                 ..Default::default()
             };
@@ -822,10 +1365,43 @@ impl DockerClient {
             let mut size = 0;
             let mut file_tree = vec![];
             // 只有非空的layer需要获取files
-            if !empty {
+            if let Some(index) = *manifest_index {
                 // manifest中的layer只对应非空的操作
                 if let Some(value) = manifest.layers.get(index) {
                     info = info_list.get(index).unwrap();
+                    diff_id = layer_diff_id.clone().unwrap_or_default();
+                    // 校验解压后数据的digest
+                    if let Some(ref actual) = info.diff_id {
+                        if !diff_id.is_empty() {
+                            let verified = *actual == diff_id;
+                            if !verified {
+                                tl_warn!(
+                                    digest = value.digest,
+                                    expected = diff_id,
+                                    actual,
+                                    "diff id mismatch"
+                                );
+                                diff_id_mismatch_list.push(DiffIdMismatchInfo {
+                                    layer_index,
+                                    expected: diff_id.clone(),
+                                    actual: actual.clone(),
+                                });
+                            }
+                            diff_id_verified = Some(verified);
+                        }
+                    }
+                    // 无法分析或解析失败的layer已单独标记
+                    if diff_id_verified.is_none()
+                        && info.unsupported.is_none()
+                        && info.error.is_none()
+                    {
+                        let reason = if info.diff_id.is_none() {
+                            "listed from toc without decompression"
+                        } else {
+                            "diff id not found in rootfs"
+                        };
+                        diff_id_unverified = Some(reason.to_string());
+                    }
                     size = value.size;
                     digest = value.digest.clone();
                     media_type = value.media_type.clone();
@@ -837,6 +1413,58 @@ impl DockerClient {
                             &file_tree_list,
                         );
                     }
+                    for file in merged_file_system.apply(layer_index, &info.files) {
+                        removed_file_list.push((layer_index, file));
+                    }
+                    for binary in info.elf_binary_list.iter() {
+                        elf_binary_list.push(ElfBinaryInfo {
+                            layer_index,
+                            ..binary.clone()
+                        });
+                    }
+                    for component in info.component_list.iter() {
+                        component_list.push(LanguageComponent {
+                            layer_index,
+                            ..component.clone()
+                        });
+                    }
+                    for db in info.package_db_list.iter() {
+                        package_db_list.push((layer_index, db.clone()));
+                    }
+                    for ld_config in info.ld_config_list.iter() {
+                        ld_config_list.push((layer_index, ld_config.clone()));
+                    }
+                    // whiteout同样会删除目录
+                    let dirs: Vec<ImageFileInfo> = info
+                        .dir_list
+                        .iter()
+                        .chain(info.files.iter().filter(|file| file.is_whiteout.is_some()))
+                        .cloned()
+                        .collect();
+                    merged_dir_system.apply(layer_index, &dirs);
+                    for account in info.account_list.iter() {
+                        account_list.push((layer_index, account.clone()));
+                    }
+                    if let Some(ref tar_header) = info.tar_header {
+                        let mut tar_header = tar_header.clone();
+                        tar_header.layer_index = layer_index;
+                        tar_header_list.push(tar_header);
+                    }
+                    for item in info.file_type_list.iter() {
+                        add_file_type_size(
+                            &mut file_type_list,
+                            &item.file_type,
+                            item.count,
+                            item.size,
+                        );
+                    }
+                    // 后续删除的文件也保留其敏感信息
+                    for finding in info.secret_list.iter() {
+                        secret_list.push(SecretFinding {
+                            layer_index: Some(layer_index),
+                            ..finding.clone()
+                        });
+                    }
                     image_size += info.size;
                     image_total_size += info.unpack_size;
                     if is_new {
@@ -854,7 +1482,6 @@ impl DockerClient {
                     // TODO 根据file summary判断文件是否更新或删除
                     file_tree = convert_files_to_file_tree(&info.files, &file_summary_list);
                 }
-                index += 1;
             }
 
             let created_by = if let Some(ref value) = history.created_by {
@@ -868,11 +1495,68 @@ impl DockerClient {
                 cmd: created_by,
                 empty,
                 digest,
+                diff_id,
+                diff_id_verified,
+                diff_id_unverified,
+                content_skipped: info.content_skipped.clone(),
                 media_type,
                 unpack_size: info.unpack_size,
                 size,
+                toc: info.toc.clone(),
+                unsupported: info.unsupported.clone(),
+                error: info.error.clone(),
+                base,
+                file_type_list: info.file_type_list.clone(),
+                compression: info.compression.clone(),
             });
             file_tree_list.push(file_tree);
+            layer_file_list.push(info.files.clone());
+        }
+
+        let merged_file_list = merged_file_system.files();
+        let merged_file_tree = convert_merged_files_to_file_tree(
+            &merged_file_list,
+            layer_file_list.len().saturating_sub(1),
+        );
+        let duplicate_file_list = find_duplicate_files(&merged_file_list);
+        let ghost_file_list = find_ghost_files(&layers, &removed_file_list);
+        let package_cache_list = find_package_caches(&merged_file_list);
+        let sbom = build_sbom(&package_db_list, &merged_file_list);
+        // 仅保留最终文件系统中仍存在的组件
+        let final_files: HashSet<(&str, usize)> = merged_file_list
+            .iter()
+            .map(|item| (item.info.path.as_str(), item.layer_index))
+            .collect();
+        component_list.retain(|item: &LanguageComponent| {
+            let path = item.path.split("!/").next().unwrap_or_default();
+            final_files.contains(&(path.trim_start_matches('/'), item.layer_index))
+        });
+        component_list.sort_by_key(|item| std::cmp::Reverse(item.size));
+        elf_binary_list.retain(|item: &ElfBinaryInfo| {
+            final_files.contains(&(item.path.trim_start_matches('/'), item.layer_index))
+        });
+        // 仅使用最终文件系统中的动态链接器配置
+        let ld_config_list: Vec<_> = ld_config_list
+            .into_iter()
+            .filter(|(layer_index, item)| final_files.contains(&(item.path.as_str(), *layer_index)))
+            .map(|(_, item)| item)
+            .collect();
+        let missing_library_list =
+            find_missing_libraries(&merged_file_system, &elf_binary_list, &ld_config_list);
+        elf_binary_list.sort_by_key(|item| std::cmp::Reverse(item.strip_savings()));
+        file_type_list.sort_by_key(|item: &FileTypeSize| std::cmp::Reverse(item.size));
+        let vulnerability_list = get_osv_database()
+            .map(|db| find_vulnerabilities(db, &sbom, &component_list))
+            .unwrap_or_default();
+        let incomplete = layers.iter().any(|layer| layer.error.is_some());
+        if let Some(ref mut info) = base_image {
+            for layer in layers.iter().filter(|layer| layer.base) {
+                info.layer_count += 1;
+                info.size += layer.size;
+                info.total_size += layer.unpack_size;
+            }
+            info.app_size = image_size.saturating_sub(info.size);
+            info.app_total_size = image_total_size.saturating_sub(info.total_size);
         }
 
         tl_info!(user = user, img = img, tag = tag, "analyze image done",);
@@ -893,6 +1577,55 @@ impl DockerClient {
             }
         }
 
+        let account_list: Vec<_> = account_list
+            .into_iter()
+            .filter(|(layer_index, item)| final_files.contains(&(item.path.as_str(), *layer_index)))
+            .map(|(_, item)| item)
+            .collect();
+        let image_config = config.config.clone().unwrap_or_default();
+        let mut command = image_config.entrypoint.clone().unwrap_or_default();
+        command.extend(image_config.cmd.clone().unwrap_or_default());
+        let working_dir = image_config.working_dir.clone().unwrap_or_default();
+        let merged_dir_list = merged_dir_system.files();
+        let permission_finding_list = audit_permissions(PermissionAuditOption {
+            fs: &merged_file_system,
+            dirs: &merged_dir_list,
+            accounts: &account_list,
+            user: &user,
+            envs: &envs,
+            command: &command,
+            working_dir: &working_dir,
+        });
+        let mut lint_issue_list = lint_image(
+            &get_lint_rules(),
+            &merged_file_list,
+            &merged_dir_list,
+            &user,
+            &missing_library_list,
+        );
+        lint_issue_list.append(&mut check_image_config(ConfigCheckOption {
+            fs: &merged_file_system,
+            config: &image_config,
+            history: &config.history,
+            annotations: &manifest.annotations.clone().unwrap_or_default(),
+            incomplete,
+            severities: &get_config_checks(),
+        }));
+        let (uids, gids) = get_account_ids(&account_list);
+        let reproducibility = audit_reproducibility(ReproducibilityOption {
+            history: &config.history,
+            files: &merged_file_list,
+            uids: &uids,
+            gids: &gids,
+            tar_headers: tar_header_list,
+            binaries: &elf_binary_list,
+            source_date_epoch: params.source_date_epoch,
+        });
+        if let Some(scanner) = get_secret_scanner() {
+            secret_list.append(&mut scanner.scan_config("ENV", &envs));
+            secret_list.append(&mut scanner.scan_config("LABEL", &labels));
+        }
+
         Ok(DockerAnalyzeResult {
             name: format!("{user}/{img}:{tag}"),
             arch: config.architecture,
@@ -904,8 +1637,28 @@ impl DockerClient {
             size: image_size,
             total_size: image_total_size,
             file_tree_list,
+            merged_file_tree,
+            layer_file_list,
+            merged_file_list,
             file_summary_list,
             big_modified_file_list,
+            duplicate_file_list,
+            ghost_file_list,
+            incomplete,
+            warning_list,
+            diff_id_mismatch_list,
+            base_image,
+            package_cache_list,
+            lint_issue_list,
+            secret_list,
+            sbom,
+            component_list,
+            vulnerability_list,
+            elf_binary_list,
+            file_type_list,
+            permission_finding_list,
+            image_config,
+            reproducibility,
         })
     }
 }
@@ -923,6 +1676,8 @@ pub async fn analyze_docker_image(image_info: ImageInfo) -> Result<DockerAnalyze
         let c = DockerClient::new(REGISTRY_LOCAL_FILE);
         c.analyze(&mut DockerImageParams {
             img: filename,
+            base: image_info.base,
+            source_date_epoch: image_info.source_date_epoch,
             ..Default::default()
         })
         .await
@@ -933,6 +1688,8 @@ pub async fn analyze_docker_image(image_info: ImageInfo) -> Result<DockerAnalyze
             img: image_info.name,
             tag: image_info.tag,
             arch: image_info.arch,
+            base: image_info.base,
+            source_date_epoch: image_info.source_date_epoch,
             ..Default::default()
         })
         .await
@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::ImageMergedFile;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateFileGroup {
+    // 文件内容的sha256
+    pub digest: String,
+    // 单个文件大小
+    pub size: u64,
+    // 去重（或使用硬链接）后可节省的空间
+    pub wasted_size: u64,
+    // 内容相同的文件
+    pub files: Vec<ImageMergedFile>,
+}
+
+// 从合并后的文件系统中查找内容相同的文件
+pub fn find_duplicate_files(files: &[ImageMergedFile]) -> Vec<DuplicateFileGroup> {
+    let mut groups: HashMap<String, Vec<ImageMergedFile>> = HashMap::new();
+    for file in files.iter() {
+        // 空文件与链接忽略
+        if file.info.size == 0 || !file.info.link.is_empty() {
+            continue;
+        }
+        if let Some(ref digest) = file.info.digest {
+            groups.entry(digest.clone()).or_default().push(file.clone());
+        }
+    }
+    let mut duplicate_file_list: Vec<DuplicateFileGroup> = groups
+        .into_iter()
+        .filter(|(_, files)| files.len() > 1)
+        .map(|(digest, files)| {
+            let size = files[0].info.size;
+            DuplicateFileGroup {
+                digest,
+                size,
+                wasted_size: size * (files.len() as u64 - 1),
+                files,
+            }
+        })
+        .collect();
+    duplicate_file_list.sort_by(|a, b| {
+        b.wasted_size
+            .cmp(&a.wasted_size)
+            .then_with(|| a.digest.cmp(&b.digest))
+    });
+    duplicate_file_list
+}
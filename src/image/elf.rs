@@ -0,0 +1,123 @@
+use goblin::elf::{note::NT_GNU_BUILD_ID, Elf};
+use serde::{Deserialize, Serialize};
+
+use super::{find_elf_components, LanguageComponent};
+
+static ELF_MAGIC: &[u8] = b"\x7fELF";
+
+// 大于此大小的文件不解析
+static MAX_ELF_FILE_SIZE: u64 = 512 * 1024 * 1024;
+
+// Go的build id的note类型
+static NT_GO_BUILD_ID: u32 = 4;
+
+static LINKAGE_STATIC: &str = "static";
+static LINKAGE_DYNAMIC: &str = "dynamic";
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElfBinaryInfo {
+    pub path: String,
+    pub size: u64,
+    // 所在的layer
+    pub layer_index: usize,
+    // 链接方式：static或dynamic
+    pub linkage: String,
+    // 动态链接器，如/lib64/ld-linux-x86-64.so.2
+    pub interpreter: String,
+    // 依赖的动态库（DT_NEEDED）
+    pub needed: Vec<String>,
+    // RPATH与RUNPATH中的目录
+    pub runpath: Vec<String>,
+    // .debug_*段的大小
+    pub debug_size: u64,
+    // 符号表（.symtab与.strtab）的大小
+    pub symbol_size: u64,
+    // GNU或Go的build id
+    pub build_id: String,
+}
+
+impl ElfBinaryInfo {
+    // strip后可节省的空间
+    pub fn strip_savings(&self) -> u64 {
+        self.debug_size + self.symbol_size
+    }
+}
+
+// 可执行文件与动态库需要解析
+pub fn is_elf_candidate(path: &str, size: u64, executable: bool) -> bool {
+    if size < 64 || size > MAX_ELF_FILE_SIZE {
+        return false;
+    }
+    let name = path.rsplit('/').next().unwrap_or_default();
+    executable || name.ends_with(".so") || name.contains(".so.")
+}
+
+// 根据文件头判断是否为elf文件
+pub fn is_elf_content(head: &[u8]) -> bool {
+    head.starts_with(ELF_MAGIC)
+}
+
+// GNU的build id为hex格式，Go的build id为字符串
+fn get_build_id(elf: &Elf, content: &[u8]) -> String {
+    let Some(notes) = elf.iter_note_sections(content, None) else {
+        return "".to_string();
+    };
+    for note in notes.flatten() {
+        if note.name == "GNU" && note.n_type == NT_GNU_BUILD_ID {
+            return hex::encode(note.desc);
+        }
+        if note.name == "Go" && note.n_type == NT_GO_BUILD_ID {
+            return String::from_utf8_lossy(note.desc).to_string();
+        }
+    }
+    "".to_string()
+}
+
+// 解析elf文件，返回二进制信息及其中嵌入的依赖
+pub fn analyze_elf(path: &str, content: &[u8]) -> Option<(ElfBinaryInfo, Vec<LanguageComponent>)> {
+    if !is_elf_content(content) {
+        return None;
+    }
+    let elf = Elf::parse(content).ok()?;
+    let path = format!("/{path}");
+    let mut debug_size = 0;
+    let mut symbol_size = 0;
+    for section in elf.section_headers.iter() {
+        let name = elf.shdr_strtab.get_at(section.sh_name).unwrap_or_default();
+        // NOBITS的段不占用文件空间
+        if section.sh_type == goblin::elf::section_header::SHT_NOBITS {
+            continue;
+        }
+        if name.starts_with(".debug_") || name.starts_with(".zdebug_") {
+            debug_size += section.sh_size;
+        } else if name == ".symtab" || name == ".strtab" {
+            symbol_size += section.sh_size;
+        }
+    }
+    let linkage = if elf.interpreter.is_some() || !elf.libraries.is_empty() {
+        LINKAGE_DYNAMIC
+    } else {
+        LINKAGE_STATIC
+    };
+    let build_id = get_build_id(&elf, content);
+    let info = ElfBinaryInfo {
+        path: path.clone(),
+        size: content.len() as u64,
+        linkage: linkage.to_string(),
+        interpreter: elf.interpreter.unwrap_or_default().to_string(),
+        needed: elf.libraries.iter().map(|item| item.to_string()).collect(),
+        runpath: elf
+            .rpaths
+            .iter()
+            .chain(elf.runpaths.iter())
+            .map(|item| item.to_string())
+            .collect(),
+        debug_size,
+        symbol_size,
+        build_id,
+        ..Default::default()
+    };
+    let components = find_elf_components(&path, &elf, content);
+    Some((info, components))
+}
@@ -0,0 +1,260 @@
+use std::collections::BTreeMap;
+
+use super::{ImageFileInfo, ImageMergedFile};
+
+// 解析符号链接的最大次数，避免循环链接
+static MAX_SYMLINK_COUNT: usize = 40;
+
+// `.wh..wh..opq`去除whiteout前缀后的文件名
+// 表示该目录下层的所有内容均被隐藏
+static OPAQUE_WHITEOUT: &str = ".opq";
+
+// 按层叠加后的文件系统
+#[derive(Default, Debug, Clone)]
+pub struct MergedFileSystem {
+    files: BTreeMap<String, ImageMergedFile>,
+}
+
+impl MergedFileSystem {
+    // 删除该路径以及其子目录下的所有文件，返回被删除的文件
+    fn remove(&mut self, path: &str) -> Vec<ImageMergedFile> {
+        // 根目录
+        if path.is_empty() {
+            return std::mem::take(&mut self.files).into_values().collect();
+        }
+        let mut removed = vec![];
+        if let Some(file) = self.files.remove(path) {
+            removed.push(file);
+        }
+        removed.extend(self.remove_children(path));
+        removed
+    }
+    // 删除该目录下的所有文件（不包括该目录），返回被删除的文件
+    fn remove_children(&mut self, path: &str) -> Vec<ImageMergedFile> {
+        let mut removed = vec![];
+        let prefix = format!("{path}/");
+        let keys: Vec<String> = self
+            .files
+            .range(prefix.clone()..)
+            .take_while(|(key, _)| key.starts_with(&prefix))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in keys {
+            if let Some(file) = self.files.remove(&key) {
+                removed.push(file);
+            }
+        }
+        removed
+    }
+    // 将该层的文件叠加至文件系统，返回该层删除的文件
+    // 先处理删除再处理新增，与overlay的处理一致
+    pub fn apply(&mut self, layer_index: usize, files: &[ImageFileInfo]) -> Vec<ImageMergedFile> {
+        let mut removed = vec![];
+        for file in files.iter().filter(|file| file.is_whiteout.is_some()) {
+            match file.path.strip_suffix(OPAQUE_WHITEOUT) {
+                Some(dir) if dir.is_empty() || dir.ends_with('/') => {
+                    removed.extend(self.remove(dir.trim_end_matches('/')));
+                }
+                _ => removed.extend(self.remove(&file.path)),
+            }
+        }
+        for file in files.iter().filter(|file| file.is_whiteout.is_none()) {
+            // 非目录替换下层的目录时，下层目录中的内容均被隐藏
+            if !file.mode.starts_with('d') {
+                removed.extend(self.remove_children(&file.path));
+            }
+            // 下层同名的文件被目录替换（符号链接不处理）
+            let mut parent = file.path.as_str();
+            while let Some((dir, _)) = parent.rsplit_once('/') {
+                if self
+                    .files
+                    .get(dir)
+                    .is_some_and(|item| !item.info.mode.starts_with(['d', 'l']))
+                {
+                    removed.extend(self.files.remove(dir));
+                }
+                parent = dir;
+            }
+            self.files.insert(
+                file.path.clone(),
+                ImageMergedFile {
+                    layer_index,
+                    info: file.clone(),
+                },
+            );
+        }
+        removed
+    }
+    // 解析路径中的符号链接（包括目录的符号链接），返回最终指向的文件
+    pub fn resolve(&self, path: &str) -> Option<&ImageMergedFile> {
+        let mut pending: Vec<String> = path.rsplit('/').map(|item| item.to_string()).collect();
+        let mut current: Vec<String> = vec![];
+        let mut count = 0;
+        while let Some(name) = pending.pop() {
+            match name.as_str() {
+                "" | "." => continue,
+                ".." => {
+                    current.pop();
+                    continue;
+                }
+                _ => {}
+            }
+            current.push(name);
+            let Some(file) = self.files.get(&current.join("/")) else {
+                continue;
+            };
+            if !file.info.mode.starts_with('l') {
+                continue;
+            }
+            count += 1;
+            if count > MAX_SYMLINK_COUNT {
+                return None;
+            }
+            current.pop();
+            // 绝对路径从根目录开始解析
+            if file.info.link.starts_with('/') {
+                current.clear();
+            }
+            pending.extend(file.info.link.rsplit('/').map(|item| item.to_string()));
+        }
+        self.files.get(&current.join("/"))
+    }
+    // 当前文件系统的所有文件（按路径排序）
+    pub fn files(&self) -> Vec<ImageMergedFile> {
+        self.files.values().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MergedFileSystem;
+    use crate::image::ImageFileInfo;
+
+    fn new_file(path: &str) -> ImageFileInfo {
+        ImageFileInfo {
+            path: path.to_string(),
+            mode: "-rw-r--r--".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn new_whiteout(path: &str) -> ImageFileInfo {
+        ImageFileInfo {
+            path: path.to_string(),
+            is_whiteout: Some(true),
+            ..Default::default()
+        }
+    }
+
+    fn new_symlink(path: &str, link: &str) -> ImageFileInfo {
+        ImageFileInfo {
+            path: path.to_string(),
+            link: link.to_string(),
+            mode: "lrwxrwxrwx".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn paths(fs: &MergedFileSystem) -> Vec<String> {
+        fs.files().into_iter().map(|item| item.info.path).collect()
+    }
+
+    #[test]
+    fn test_apply_override() {
+        let mut fs = MergedFileSystem::default();
+        fs.apply(0, &[new_file("etc/hosts"), new_file("bin/sh")]);
+        let removed = fs.apply(1, &[new_file("etc/hosts")]);
+        assert!(removed.is_empty());
+        assert_eq!(vec!["bin/sh", "etc/hosts"], paths(&fs));
+        assert_eq!(1, fs.resolve("etc/hosts").unwrap().layer_index);
+        assert_eq!(0, fs.resolve("bin/sh").unwrap().layer_index);
+    }
+
+    #[test]
+    fn test_apply_whiteout() {
+        let mut fs = MergedFileSystem::default();
+        fs.apply(
+            0,
+            &[
+                new_file("usr/bin/curl"),
+                new_file("usr/lib/a.so"),
+                new_file("usr/lib/b.so"),
+                new_file("usr/libexec/c"),
+            ],
+        );
+        // 删除文件与目录，同一层中的新增文件保留
+        let removed = fs.apply(
+            1,
+            &[
+                new_whiteout("usr/bin/curl"),
+                new_whiteout("usr/lib"),
+                new_file("usr/lib/d.so"),
+            ],
+        );
+        let mut removed: Vec<String> = removed.into_iter().map(|item| item.info.path).collect();
+        removed.sort();
+        assert_eq!(
+            vec!["usr/bin/curl", "usr/lib/a.so", "usr/lib/b.so"],
+            removed
+        );
+        assert_eq!(vec!["usr/lib/d.so", "usr/libexec/c"], paths(&fs));
+    }
+
+    #[test]
+    fn test_apply_opaque_dir() {
+        let mut fs = MergedFileSystem::default();
+        fs.apply(
+            0,
+            &[new_file("app/a"), new_file("app/sub/b"), new_file("apps/c")],
+        );
+        let removed = fs.apply(1, &[new_whiteout("app/.opq"), new_file("app/d")]);
+        assert_eq!(2, removed.len());
+        assert_eq!(vec!["app/d", "apps/c"], paths(&fs));
+
+        // 根目录的opaque whiteout
+        let removed = fs.apply(2, &[new_whiteout(".opq"), new_file("e")]);
+        assert_eq!(2, removed.len());
+        assert_eq!(vec!["e"], paths(&fs));
+    }
+
+    #[test]
+    fn test_apply_replace_file_with_dir() {
+        let mut fs = MergedFileSystem::default();
+        fs.apply(0, &[new_file("opt/app"), new_file("opt/other")]);
+        let removed = fs.apply(1, &[new_file("opt/app/bin/run")]);
+        assert_eq!(1, removed.len());
+        assert_eq!("opt/app", removed[0].info.path);
+        assert_eq!(vec!["opt/app/bin/run", "opt/other"], paths(&fs));
+    }
+
+    #[test]
+    fn test_apply_replace_dir_with_file() {
+        let mut fs = MergedFileSystem::default();
+        fs.apply(
+            0,
+            &[new_file("opt/app/bin/run"), new_file("opt/app/lib/a.so")],
+        );
+        let removed = fs.apply(1, &[new_symlink("opt/app", "/srv/app")]);
+        assert_eq!(2, removed.len());
+        assert_eq!(vec!["opt/app"], paths(&fs));
+    }
+
+    #[test]
+    fn test_resolve_symlink() {
+        let mut fs = MergedFileSystem::default();
+        fs.apply(
+            0,
+            &[
+                new_file("usr/lib/libc.so.6"),
+                new_symlink("lib", "usr/lib"),
+                new_symlink("usr/lib/libc.so", "libc.so.6"),
+                new_symlink("loop/a", "b"),
+                new_symlink("loop/b", "a"),
+            ],
+        );
+        let file = fs.resolve("/lib/libc.so").unwrap();
+        assert_eq!("usr/lib/libc.so.6", file.info.path);
+        assert!(fs.resolve("/loop/a").is_none());
+        assert!(fs.resolve("/lib/not-exist.so").is_none());
+    }
+}
@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+
+// 识别文件类型时读取的文件头大小（tar的magic位于257）
+pub static FILE_TYPE_HEADER_SIZE: u64 = 512;
+
+static FILE_TYPE_EXECUTABLE: &str = "executable";
+static FILE_TYPE_SHARED_LIBRARY: &str = "shared library";
+static FILE_TYPE_SCRIPT: &str = "script";
+static FILE_TYPE_ARCHIVE: &str = "archive";
+static FILE_TYPE_IMAGE: &str = "image";
+static FILE_TYPE_FONT: &str = "font";
+static FILE_TYPE_LOCALE: &str = "locale";
+static FILE_TYPE_MAN_PAGE: &str = "man page";
+static FILE_TYPE_PYTHON_BYTECODE: &str = "python bytecode";
+static FILE_TYPE_SOURCE_CODE: &str = "source code";
+static FILE_TYPE_OTHER: &str = "other";
+
+// 文件头的magic与对应的类型
+static MAGIC_LIST: &[(usize, &[u8], &str)] = &[
+    (0, b"#!", FILE_TYPE_SCRIPT),
+    (0, b"\x1f\x8b", FILE_TYPE_ARCHIVE),
+    (0, b"PK\x03\x04", FILE_TYPE_ARCHIVE),
+    (0, b"\xfd7zXZ\x00", FILE_TYPE_ARCHIVE),
+    (0, b"BZh", FILE_TYPE_ARCHIVE),
+    (0, b"\x28\xb5\x2f\xfd", FILE_TYPE_ARCHIVE),
+    (0, b"7z\xbc\xaf\x27\x1c", FILE_TYPE_ARCHIVE),
+    (0, b"!<arch>\n", FILE_TYPE_ARCHIVE),
+    (257, b"ustar", FILE_TYPE_ARCHIVE),
+    (0, b"\x89PNG", FILE_TYPE_IMAGE),
+    (0, b"\xff\xd8\xff", FILE_TYPE_IMAGE),
+    (0, b"GIF8", FILE_TYPE_IMAGE),
+    (8, b"WEBP", FILE_TYPE_IMAGE),
+    (0, b"\x00\x01\x00\x00\x00", FILE_TYPE_FONT),
+    (0, b"OTTO", FILE_TYPE_FONT),
+    (0, b"wOFF", FILE_TYPE_FONT),
+    (0, b"wOF2", FILE_TYPE_FONT),
+    (0, b"\xde\x12\x04\x95", FILE_TYPE_LOCALE),
+    (0, b"\x95\x04\x12\xde", FILE_TYPE_LOCALE),
+];
+
+// 文件后缀与对应的类型
+static EXTENSION_LIST: &[(&[&str], &str)] = &[
+    (&["sh", "bash", "zsh"], FILE_TYPE_SCRIPT),
+    (
+        &[
+            "gz", "tgz", "zip", "jar", "war", "ear", "whl", "egg", "xz", "bz2", "zst", "7z", "tar",
+            "deb", "rpm", "apk", "a",
+        ],
+        FILE_TYPE_ARCHIVE,
+    ),
+    (
+        &["png", "jpg", "jpeg", "gif", "webp", "svg", "ico", "bmp"],
+        FILE_TYPE_IMAGE,
+    ),
+    (
+        &["ttf", "otf", "ttc", "woff", "woff2", "pfb", "pcf", "afm"],
+        FILE_TYPE_FONT,
+    ),
+    (&["mo"], FILE_TYPE_LOCALE),
+    (&["pyc", "pyo"], FILE_TYPE_PYTHON_BYTECODE),
+    (
+        &[
+            "py", "js", "mjs", "cjs", "ts", "tsx", "jsx", "go", "rs", "c", "h", "cc", "cpp", "hpp",
+            "java", "kt", "scala", "rb", "php", "pl", "pm", "lua", "swift", "cs",
+        ],
+        FILE_TYPE_SOURCE_CODE,
+    ),
+];
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileTypeSize {
+    // 文件类型
+    pub file_type: String,
+    // 文件数量
+    pub count: usize,
+    // 文件大小
+    pub size: u64,
+}
+
+// 以.so结尾或.so后为版本号，如libc.so.6
+fn is_shared_library_name(name: &str) -> bool {
+    if name.ends_with(".so") {
+        return true;
+    }
+    let Some((_, version)) = name.split_once(".so.") else {
+        return false;
+    };
+    version.chars().all(|c| c.is_ascii_digit() || c == '.')
+}
+
+// 根据路径、文件头以及是否可执行识别文件类型
+// 无法读取文件内容时header为空，仅根据路径识别
+pub fn detect_file_type(path: &str, header: &[u8], executable: bool) -> &'static str {
+    let name = path.rsplit('/').next().unwrap_or_default();
+    // man page与locale通常为压缩文件，优先根据目录判断
+    if path.contains("share/man/") {
+        return FILE_TYPE_MAN_PAGE;
+    }
+    if path.contains("/locale/") || path.starts_with("usr/lib/locale/") {
+        return FILE_TYPE_LOCALE;
+    }
+    if header.starts_with(b"\x7fELF") {
+        // e_type为ET_DYN时，可能是动态库或PIE的可执行文件
+        if header.get(16) == Some(&3) && is_shared_library_name(name) {
+            return FILE_TYPE_SHARED_LIBRARY;
+        }
+        return FILE_TYPE_EXECUTABLE;
+    }
+    if is_shared_library_name(name) {
+        return FILE_TYPE_SHARED_LIBRARY;
+    }
+    for (offset, magic, file_type) in MAGIC_LIST.iter() {
+        if header
+            .get(*offset..offset + magic.len())
+            .is_some_and(|value| value == *magic)
+        {
+            return file_type;
+        }
+    }
+    if let Some((_, ext)) = name.rsplit_once('.') {
+        let ext = ext.to_lowercase();
+        for (extensions, file_type) in EXTENSION_LIST.iter() {
+            if extensions.contains(&ext.as_str()) {
+                return file_type;
+            }
+        }
+    }
+    // 无文件内容时，可执行文件视为二进制
+    if header.is_empty() && executable {
+        return FILE_TYPE_EXECUTABLE;
+    }
+    FILE_TYPE_OTHER
+}
+
+// 汇总该类型文件的数量与大小
+pub fn add_file_type_size(list: &mut Vec<FileTypeSize>, file_type: &str, count: usize, size: u64) {
+    if let Some(item) = list.iter_mut().find(|item| item.file_type == file_type) {
+        item.count += count;
+        item.size += size;
+        return;
+    }
+    list.push(FileTypeSize {
+        file_type: file_type.to_string(),
+        count,
+        size,
+    });
+}
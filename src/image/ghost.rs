@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+use super::{ImageLayer, ImageMergedFile};
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GhostFileInfo {
+    pub path: String,
+    // 文件大小（仍占用镜像的空间）
+    pub size: u64,
+    // 添加该文件的层
+    pub added_layer_index: usize,
+    pub added_cmd: String,
+    // 删除该文件的层
+    pub removed_layer_index: usize,
+    pub removed_cmd: String,
+}
+
+// 在某层添加而在后续层删除的文件
+// 此类文件在最终的文件系统中不可见，但仍占用镜像空间
+pub fn find_ghost_files(
+    layers: &[ImageLayer],
+    removed_file_list: &[(usize, ImageMergedFile)],
+) -> Vec<GhostFileInfo> {
+    let get_cmd = |index: usize| -> String {
+        layers
+            .get(index)
+            .map(|layer| layer.cmd.clone())
+            .unwrap_or_default()
+    };
+    let mut ghost_file_list: Vec<GhostFileInfo> = removed_file_list
+        .iter()
+        .filter(|(_, file)| file.info.size > 0)
+        .map(|(removed_layer_index, file)| GhostFileInfo {
+            path: file.info.path.clone(),
+            size: file.info.size,
+            added_layer_index: file.layer_index,
+            added_cmd: get_cmd(file.layer_index),
+            removed_layer_index: *removed_layer_index,
+            removed_cmd: get_cmd(*removed_layer_index),
+        })
+        .collect();
+    ghost_file_list.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+    ghost_file_list
+}
@@ -0,0 +1,357 @@
+use goblin::elf::Elf;
+use libflate::zlib::Decoder;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+
+use super::ImageFileInfo;
+
+// 大于此大小的elf文件与jar不解析（需读取全部内容）
+static MAX_INVENTORY_FILE_SIZE: u64 = 128 * 1024 * 1024;
+
+static GO_BUILDINFO_MAGIC: &[u8] = b"\xff Go buildinf:";
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageComponent {
+    // 生态：npm、pypi、golang、cargo、maven
+    pub ecosystem: String,
+    pub name: String,
+    pub version: String,
+    // 识别出该组件的文件
+    pub path: String,
+    // 组件占用的空间（嵌入在二进制中的依赖为0）
+    pub size: u64,
+    // 所在的layer
+    pub layer_index: usize,
+}
+
+fn is_npm_package_json(path: &str) -> bool {
+    let Some(dir) = path.strip_suffix("/package.json") else {
+        return false;
+    };
+    get_npm_package_dir(dir).is_some_and(|item| item == dir)
+}
+
+fn is_python_metadata(path: &str) -> bool {
+    path.ends_with(".dist-info/METADATA") || path.ends_with(".dist-info/RECORD")
+}
+
+fn is_jar(path: &str) -> bool {
+    path.ends_with(".jar") || path.ends_with(".war") || path.ends_with(".ear")
+}
+
+// 是否需要读取文件内容识别语言组件
+pub fn is_inventory_file(path: &str, size: u64, elf_executable: bool) -> bool {
+    if size == 0 || size > MAX_INVENTORY_FILE_SIZE {
+        return false;
+    }
+    // elf可执行文件（go或rust的二进制），由调用方根据文件头判断
+    elf_executable || is_npm_package_json(path) || is_python_metadata(path) || is_jar(path)
+}
+
+// 文件所在的npm包目录，如node_modules/@types/node
+fn get_npm_package_dir(path: &str) -> Option<&str> {
+    let keyword = "node_modules/";
+    let index = path.rfind(keyword)? + keyword.len();
+    let rest = &path[index..];
+    let mut count = if rest.starts_with('@') { 2 } else { 1 };
+    let mut end = index;
+    for item in rest.split('/') {
+        if count == 0 || item.is_empty() {
+            break;
+        }
+        end += item.len() + 1;
+        count -= 1;
+    }
+    if count != 0 {
+        return None;
+    }
+    Some(&path[..end - 1])
+}
+
+fn parse_npm_package(path: &str, content: &[u8]) -> Option<LanguageComponent> {
+    let value: serde_json::Value = serde_json::from_slice(content).ok()?;
+    let name = value.get("name")?.as_str()?.to_string();
+    let version = value
+        .get("version")
+        .and_then(|item| item.as_str())
+        .unwrap_or_default()
+        .to_string();
+    Some(LanguageComponent {
+        ecosystem: "npm".to_string(),
+        name,
+        version,
+        path: path.to_string(),
+        ..Default::default()
+    })
+}
+
+fn parse_python_metadata(path: &str, content: &[u8]) -> Option<LanguageComponent> {
+    let content = String::from_utf8_lossy(content);
+    let mut name = "".to_string();
+    let mut version = "".to_string();
+    for line in content.lines() {
+        // 头部信息结束
+        if line.is_empty() {
+            break;
+        }
+        if let Some(value) = line.strip_prefix("Name:") {
+            name = value.trim().to_string();
+        } else if let Some(value) = line.strip_prefix("Version:") {
+            version = value.trim().to_string();
+        }
+    }
+    if name.is_empty() {
+        return None;
+    }
+    Some(LanguageComponent {
+        ecosystem: "pypi".to_string(),
+        name,
+        version,
+        path: path.to_string(),
+        ..Default::default()
+    })
+}
+
+// RECORD中记录了包的所有文件及大小
+fn get_python_record_size(content: &[u8]) -> u64 {
+    String::from_utf8_lossy(content)
+        .lines()
+        .filter_map(|line| line.rsplit(',').next()?.parse::<u64>().ok())
+        .sum()
+}
+
+fn read_uvarint(data: &[u8]) -> Option<(u64, usize)> {
+    let mut value = 0;
+    for (index, b) in data.iter().enumerate().take(10) {
+        value |= ((b & 0x7f) as u64) << (7 * index);
+        if b & 0x80 == 0 {
+            return Some((value, index + 1));
+        }
+    }
+    None
+}
+
+// 解析go 1.18+写入的build info
+fn parse_go_buildinfo(path: &str, size: u64, data: &[u8]) -> Vec<LanguageComponent> {
+    let mut components = vec![];
+    // 旧版本的格式以指针保存，不支持
+    if data.len() < 32 || !data.starts_with(GO_BUILDINFO_MAGIC) || data[15] & 0x2 == 0 {
+        return components;
+    }
+    let mut offset = 32;
+    let mut values = vec![];
+    for _ in 0..2 {
+        let Some((length, count)) = read_uvarint(&data[offset..]) else {
+            return components;
+        };
+        let start = offset + count;
+        let Some(value) = data.get(start..start + length as usize) else {
+            return components;
+        };
+        values.push(value);
+        offset = start + length as usize;
+    }
+    let go_version = String::from_utf8_lossy(values[0]).to_string();
+    let mut mod_info = values[1];
+    // 前后各有16字节的分隔符
+    if mod_info.len() >= 33 {
+        mod_info = &mod_info[16..mod_info.len() - 16];
+    }
+    let mod_info = String::from_utf8_lossy(mod_info);
+    components.push(LanguageComponent {
+        ecosystem: "golang".to_string(),
+        name: "stdlib".to_string(),
+        version: go_version,
+        path: path.to_string(),
+        ..Default::default()
+    });
+    for line in mod_info.lines() {
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() < 3 || !["mod", "dep"].contains(&fields[0]) {
+            continue;
+        }
+        components.push(LanguageComponent {
+            ecosystem: "golang".to_string(),
+            name: fields[1].to_string(),
+            version: fields[2].to_string(),
+            path: path.to_string(),
+            // 主模块以二进制的大小计算
+            size: if fields[0] == "mod" { size } else { 0 },
+            ..Default::default()
+        });
+    }
+    components
+}
+
+// 解析cargo auditable写入的依赖信息
+fn parse_cargo_auditable(path: &str, size: u64, data: &[u8]) -> Vec<LanguageComponent> {
+    let mut components = vec![];
+    let Ok(mut decoder) = Decoder::new(data) else {
+        return components;
+    };
+    let mut buf = vec![];
+    if decoder.read_to_end(&mut buf).is_err() {
+        return components;
+    }
+    let Ok(value) = serde_json::from_slice::<serde_json::Value>(&buf) else {
+        return components;
+    };
+    let Some(packages) = value.get("packages").and_then(|item| item.as_array()) else {
+        return components;
+    };
+    for package in packages.iter() {
+        let get = |key: &str| {
+            package
+                .get(key)
+                .and_then(|item| item.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        // 仅构建时使用的依赖忽略
+        if get("kind") == "build" {
+            continue;
+        }
+        let root = package
+            .get("root")
+            .and_then(|item| item.as_bool())
+            .unwrap_or_default();
+        components.push(LanguageComponent {
+            ecosystem: "cargo".to_string(),
+            name: get("name"),
+            version: get("version"),
+            path: path.to_string(),
+            size: if root { size } else { 0 },
+            ..Default::default()
+        });
+    }
+    components
+}
+
+// 识别go或rust二进制中嵌入的依赖
+pub fn find_elf_components(path: &str, elf: &Elf, content: &[u8]) -> Vec<LanguageComponent> {
+    let size = content.len() as u64;
+    for section in elf.section_headers.iter() {
+        let Some(name) = elf.shdr_strtab.get_at(section.sh_name) else {
+            continue;
+        };
+        let Some(data) = section.file_range().and_then(|range| content.get(range)) else {
+            continue;
+        };
+        match name {
+            ".go.buildinfo" => return parse_go_buildinfo(path, size, data),
+            ".dep-v0" => return parse_cargo_auditable(path, size, data),
+            _ => {}
+        }
+    }
+    vec![]
+}
+
+fn parse_pom_properties(path: &str, content: &str) -> Option<LanguageComponent> {
+    let mut values = HashMap::new();
+    for line in content.lines() {
+        if line.starts_with('#') {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            values.insert(key.trim(), value.trim());
+        }
+    }
+    let artifact_id = values.get("artifactId")?;
+    let group_id = values.get("groupId").cloned().unwrap_or_default();
+    Some(LanguageComponent {
+        ecosystem: "maven".to_string(),
+        name: format!("{group_id}:{artifact_id}"),
+        version: values
+            .get("version")
+            .cloned()
+            .unwrap_or_default()
+            .to_string(),
+        path: path.to_string(),
+        ..Default::default()
+    })
+}
+
+// 解析jar中的pom.properties，嵌套的jar（如spring boot）也解析一层
+fn parse_jar(path: &str, content: &[u8], nested: bool) -> Vec<LanguageComponent> {
+    let mut components = vec![];
+    let Ok(mut archive) = zip::ZipArchive::new(Cursor::new(content)) else {
+        return components;
+    };
+    let mut nested_jars = vec![];
+    for index in 0..archive.len() {
+        let Ok(mut file) = archive.by_index(index) else {
+            continue;
+        };
+        let name = file.name().to_string();
+        if name.starts_with("META-INF/maven/") && name.ends_with("/pom.properties") {
+            let mut buf = String::new();
+            if file.read_to_string(&mut buf).is_ok() {
+                if let Some(component) = parse_pom_properties(path, &buf) {
+                    components.push(component);
+                }
+            }
+        } else if !nested && is_jar(&name) && file.size() <= MAX_INVENTORY_FILE_SIZE {
+            let mut buf = vec![];
+            if file.read_to_end(&mut buf).is_ok() {
+                nested_jars.push((name, buf));
+            }
+        }
+    }
+    // 仅有一个组件时则为jar本身
+    if components.len() == 1 {
+        components[0].size = content.len() as u64;
+    }
+    for (name, buf) in nested_jars {
+        components.append(&mut parse_jar(&format!("{path}!/{name}"), &buf, true));
+    }
+    components
+}
+
+// 根据文件内容识别语言组件（二进制文件使用find_elf_components）
+pub fn find_language_components(path: &str, content: &[u8]) -> Vec<LanguageComponent> {
+    let file_path = format!("/{path}");
+    if is_npm_package_json(path) {
+        return parse_npm_package(&file_path, content).into_iter().collect();
+    }
+    if path.ends_with(".dist-info/METADATA") {
+        return parse_python_metadata(&file_path, content)
+            .into_iter()
+            .collect();
+    }
+    if is_jar(path) {
+        return parse_jar(&file_path, content, false);
+    }
+    vec![]
+}
+
+// 汇总layer中npm与python组件的大小
+pub fn fill_language_component_size(
+    components: &mut [LanguageComponent],
+    files: &[ImageFileInfo],
+    records: &HashMap<String, Vec<u8>>,
+) {
+    let mut npm_sizes: HashMap<&str, u64> = HashMap::new();
+    for file in files.iter() {
+        if let Some(dir) = get_npm_package_dir(&file.path) {
+            *npm_sizes.entry(dir).or_default() += file.size;
+        }
+    }
+    for component in components.iter_mut() {
+        let path = component.path.trim_start_matches('/');
+        match component.ecosystem.as_str() {
+            "npm" => {
+                let dir = path.trim_end_matches("/package.json");
+                component.size = npm_sizes.get(dir).cloned().unwrap_or_default();
+            }
+            "pypi" => {
+                let record = path.replace("/METADATA", "/RECORD");
+                if let Some(content) = records.get(&record) {
+                    component.size = get_python_record_size(content);
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -1,13 +1,26 @@
 use crate::error::HTTPError;
 use bytes::Bytes;
-use libflate::gzip::Decoder;
+use libflate::gzip::MultiDecoder;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use snafu::{ResultExt, Snafu};
+use std::collections::HashMap;
 use std::fs::File;
 use std::{io::Read, path::Path};
-use tar::Archive;
+use tar::{Archive, Entry};
+use tracing::warn;
 
-use super::ImageFileInfo;
+use super::{
+    add_file_type_size, analyze_compression, analyze_elf, detect_file_type,
+    fill_language_component_size, find_language_components, get_secret_scanner, is_account_file,
+    is_elf_candidate, is_elf_content, is_inventory_file, is_ld_config_file, is_package_db_file,
+    parse_package_db_file, AccountFile, ElfBinaryInfo, FileTypeSize, ImageFileInfo, ImageLayerToc,
+    LanguageComponent, LayerCompression, LdConfigFile, PackageDbFile, SecretFinding,
+    TarHeaderAudit, FILE_TYPE_HEADER_SIZE,
+};
+use crate::config::{
+    get_lint_rules, get_osv_path, get_zstd_levels, get_zstd_sample_size, LintSeverity,
+};
 
 #[derive(Debug, Snafu)]
 pub enum Error {
@@ -21,6 +34,8 @@ pub enum Error {
     ZstdDecode { source: std::io::Error },
     #[snafu(display("Tar fail: {}", source))]
     Tar { source: std::io::Error },
+    #[snafu(display("Toc parse fail: {}", source))]
+    Toc { source: serde_json::Error },
 }
 
 impl From<Error> for HTTPError {
@@ -32,8 +47,9 @@ impl From<Error> for HTTPError {
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 // 解压gzip
+// eStargz等格式由多个gzip member组成，因此需要使用MultiDecoder
 fn gunzip(data: &[u8]) -> Result<Vec<u8>> {
-    let mut decoder = Decoder::new(data).context(GzipDecodeSnafu {})?;
+    let mut decoder = MultiDecoder::new(data).context(GzipDecodeSnafu {})?;
     let mut decode_data = vec![];
     let _ = decoder
         .read_to_end(&mut decode_data)
@@ -48,6 +64,11 @@ pub fn zstd_decode(data: &[u8]) -> Result<Vec<u8>> {
     Ok(buf)
 }
 
+// 计算数据的sha256 digest
+pub fn get_sha256_digest(data: &[u8]) -> String {
+    format!("sha256:{}", hex::encode(Sha256::digest(data)))
+}
+
 // 从tar中读取文件信息
 pub async fn get_file_size_from_tar(tar: &str, filename: &str) -> Result<u64> {
     let file = File::open(tar).context(TarSnafu {})?;
@@ -132,33 +153,418 @@ pub struct ImageLayerInfo {
     pub unpack_size: u64,
     // 文件列表
     pub files: Vec<ImageFileInfo>,
+    // eStargz或zstd:chunked的toc信息
+    pub toc: Option<ImageLayerToc>,
+    // 无法分析的原因
+    pub unsupported: Option<String>,
+    // 解析失败的出错信息
+    pub error: Option<String>,
+    // 解压后数据的digest（使用toc时不解压，因此为空）
+    pub diff_id: Option<String>,
+    // 文件中的敏感信息
+    pub secret_list: Vec<SecretFinding>,
+    // 包管理工具的数据库文件，用于生成sbom
+    pub package_db_list: Vec<PackageDbFile>,
+    // 语言生态的组件
+    pub component_list: Vec<LanguageComponent>,
+    // elf二进制文件
+    pub elf_binary_list: Vec<ElfBinaryInfo>,
+    // 动态链接器的配置文件
+    pub ld_config_list: Vec<LdConfigFile>,
+    // 按文件类型汇总的大小
+    pub file_type_list: Vec<FileTypeSize>,
+    // 目录（文件列表中不包括目录）
+    pub dir_list: Vec<ImageFileInfo>,
+    // /etc/passwd与/etc/group
+    pub account_list: Vec<AccountFile>,
+    // tar header的检测结果（使用toc时为空）
+    pub tar_header: Option<TarHeaderAudit>,
+    // 压缩率与zstd压缩大小的估算
+    pub compression: Option<LayerCompression>,
+    // 使用toc未解压时，跳过的文件内容分析
+    pub content_skipped: Option<String>,
+}
+
+static ESTARGZ_TOC_FILE: &str = "stargz.index.json";
+// eStargz中toc相关的文件，不展示在文件列表中
+static ESTARGZ_ARTIFACTS: [&str; 3] = [
+    ESTARGZ_TOC_FILE,
+    ".prefetch.landmark",
+    ".no.prefetch.landmark",
+];
+// eStargz的footer大小
+static ESTARGZ_FOOTER_SIZE: usize = 51;
+static ZSTD_CHUNKED_MANIFEST_POSITION: &str = "io.github.containers.zstd-chunked.manifest-position";
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TocEntry {
+    name: String,
+    #[serde(rename = "type")]
+    type_field: String,
+    size: Option<u64>,
+    link_name: Option<String>,
+    mode: Option<u32>,
+    uid: Option<u64>,
+    gid: Option<u64>,
+    digest: Option<String>,
+    #[serde(rename = "modtime")]
+    mod_time: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Toc {
+    entries: Vec<TocEntry>,
+}
+
+// 将whiteout文件转换为对应的删除文件
+fn convert_whiteout(path: String) -> (String, Option<bool>) {
+    // 为了实现这样的删除操作，AuFS 会在可读写层创建一个 whiteout 文件，把只读层里的文件“遮挡”起来。
+    // .wh.
+    // usr/local/bin/.wh.static
+    if let Some(filename) = Path::new(&path).file_name() {
+        let name = filename.to_string_lossy();
+        let prefix = ".wh.";
+        if name.starts_with(prefix) {
+            let path = path.replace(name.to_string().as_str(), &name.replace(prefix, ""));
+            return (path, Some(true));
+        }
+    }
+    (path, None)
+}
+
+// 从eStargz的footer中读取toc的偏移
+fn get_estargz_toc_offset(data: &[u8]) -> Option<usize> {
+    if data.len() < ESTARGZ_FOOTER_SIZE {
+        return None;
+    }
+    let footer = &data[data.len() - ESTARGZ_FOOTER_SIZE..];
+    // footer的extra字段为：%016xSTARGZ
+    let magic = b"STARGZ";
+    let index = footer.windows(magic.len()).position(|item| item == magic)?;
+    if index < 16 {
+        return None;
+    }
+    let offset = std::str::from_utf8(&footer[index - 16..index]).ok()?;
+    let offset = usize::from_str_radix(offset, 16).ok()?;
+    if offset >= data.len() - ESTARGZ_FOOTER_SIZE {
+        return None;
+    }
+    Some(offset)
+}
+
+// 读取eStargz或zstd:chunked的toc
+fn get_toc(
+    data: &[u8],
+    media_type: &str,
+    annotations: &HashMap<String, String>,
+) -> Result<Option<(ImageLayerToc, Toc)>> {
+    let (format, toc_data) = if media_type.contains("gzip") {
+        let Some(offset) = get_estargz_toc_offset(data) else {
+            return Ok(None);
+        };
+        let buf = gunzip(&data[offset..data.len() - ESTARGZ_FOOTER_SIZE])?;
+        let mut a = Archive::new(&buf[..]);
+        let mut content = vec![];
+        for file in a.entries().context(TarSnafu {})? {
+            let mut file = file.context(TarSnafu {})?;
+            if file.path().context(TarSnafu {})?.to_string_lossy() == ESTARGZ_TOC_FILE {
+                file.read_to_end(&mut content).context(ReadSnafu {})?;
+                break;
+            }
+        }
+        ("estargz", content)
+    } else if media_type.contains("zstd") {
+        // offset:length:uncompressed length:type
+        let Some(position) = annotations.get(ZSTD_CHUNKED_MANIFEST_POSITION) else {
+            return Ok(None);
+        };
+        // 任一字段无效时不使用toc，避免字段错位
+        let arr: Vec<usize> = position
+            .split(':')
+            .map(|item| item.parse::<usize>().ok())
+            .collect::<Option<Vec<usize>>>()
+            .unwrap_or_default();
+        if arr.len() != 4
+            || arr[0]
+                .checked_add(arr[1])
+                .is_none_or(|end| end > data.len())
+        {
+            warn!(position, "zstd:chunked manifest position is invalid");
+            return Ok(None);
+        }
+        ("zstd:chunked", zstd_decode(&data[arr[0]..arr[0] + arr[1]])?)
+    } else {
+        return Ok(None);
+    };
+    if toc_data.is_empty() {
+        return Ok(None);
+    }
+    let mut hasher = Sha256::new();
+    hasher.update(&toc_data);
+    let toc: Toc = serde_json::from_slice(&toc_data).context(TocSnafu {})?;
+    let entry_count = toc
+        .entries
+        .iter()
+        .filter(|item| item.type_field != "chunk")
+        .count();
+    // 普通文件的第一个分块记录在reg中，后续分块为chunk
+    let chunk_count = toc
+        .entries
+        .iter()
+        .filter(|item| item.type_field == "chunk" || item.type_field == "reg")
+        .count();
+    Ok(Some((
+        ImageLayerToc {
+            format: format.to_string(),
+            digest: format!("sha256:{}", hex::encode(hasher.finalize())),
+            size: toc_data.len() as u64,
+            entry_count,
+            chunk_count,
+        },
+        toc,
+    )))
+}
+
+// toc中的修改时间为RFC3339格式
+fn parse_toc_mod_time(entry: &TocEntry) -> i64 {
+    entry
+        .mod_time
+        .as_ref()
+        .and_then(|value| chrono::DateTime::parse_from_rfc3339(value).ok())
+        .map(|value| value.timestamp())
+        .unwrap_or_default()
+}
+
+// 记录entry的header格式以及是否带有atime/ctime、用户名或组名
+fn record_tar_header<R: Read>(audit: &mut TarHeaderAudit, file: &mut Entry<R>) -> Result<()> {
+    let mut access_time = false;
+    let mut is_pax = false;
+    if let Some(extensions) = file.pax_extensions().context(TarSnafu {})? {
+        is_pax = true;
+        for extension in extensions.flatten() {
+            if matches!(extension.key(), Ok("atime" | "ctime")) {
+                access_time = true;
+            }
+        }
+    }
+    let header = file.header();
+    let format = if is_pax {
+        "pax"
+    } else if let Some(gnu) = header.as_gnu() {
+        if gnu.atime().unwrap_or_default() != 0 || gnu.ctime().unwrap_or_default() != 0 {
+            access_time = true;
+        }
+        "gnu"
+    } else if header.as_ustar().is_some() {
+        "ustar"
+    } else {
+        "v7"
+    };
+    let owner_name = header
+        .username_bytes()
+        .is_some_and(|value| !value.is_empty())
+        || header
+            .groupname_bytes()
+            .is_some_and(|value| !value.is_empty());
+    let path = file
+        .path()
+        .context(TarSnafu {})?
+        .to_string_lossy()
+        .to_string();
+    audit.record(&path, format, access_time, owner_name);
+    Ok(())
+}
+
+// 根据toc生成文件列表，无需解压整个layer
+fn get_files_from_toc(toc: &Toc) -> Vec<ImageFileInfo> {
+    let mut files = vec![];
+    for entry in toc.entries.iter() {
+        let file_type = match entry.type_field.as_str() {
+            "reg" => 0o100000,
+            "symlink" => 0o120000,
+            "hardlink" => 0o100000,
+            "char" => 0o020000,
+            "block" => 0o060000,
+            "fifo" => 0o010000,
+            // 目录与分块不返回
+            _ => continue,
+        };
+        let name = entry.name.trim_start_matches("./").to_string();
+        if ESTARGZ_ARTIFACTS.contains(&name.as_str()) {
+            continue;
+        }
+        let (path, is_whiteout) = convert_whiteout(name);
+        let mode = (entry.mode.unwrap_or_default() & 0o7777) | file_type;
+        files.push(ImageFileInfo {
+            path,
+            link: entry.link_name.clone().unwrap_or_default(),
+            size: entry.size.unwrap_or_default(),
+            mode: unix_mode::to_string(mode),
+            uid: entry.uid.unwrap_or_default(),
+            gid: entry.gid.unwrap_or_default(),
+            mtime: parse_toc_mod_time(entry),
+            is_whiteout,
+            digest: if is_whiteout.is_none() {
+                entry.digest.clone()
+            } else {
+                None
+            },
+        });
+    }
+    files
+}
+
+// 根据toc生成目录列表
+fn get_dirs_from_toc(toc: &Toc) -> Vec<ImageFileInfo> {
+    toc.entries
+        .iter()
+        .filter(|entry| entry.type_field == "dir")
+        .filter_map(|entry| {
+            let path = entry.name.trim_start_matches("./").trim_end_matches('/');
+            if path.is_empty() || path == "." {
+                return None;
+            }
+            Some(ImageFileInfo {
+                path: path.to_string(),
+                mode: unix_mode::to_string((entry.mode.unwrap_or_default() & 0o7777) | 0o040000),
+                uid: entry.uid.unwrap_or_default(),
+                gid: entry.gid.unwrap_or_default(),
+                mtime: parse_toc_mod_time(entry),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+// 是否启用了依赖elf与语言组件的检测
+// 漏洞检测需要语言组件，shared_library规则需要elf与动态链接器的配置
+fn need_binary_content() -> bool {
+    get_osv_path().is_some()
+        || get_lint_rules()
+            .iter()
+            .any(|rule| rule.kind == "shared_library" && rule.severity != Some(LintSeverity::Off))
 }
 
 // 从分层数据中读取所有文件信息
 // "application/vnd.oci.image.layer.v1.tar+gzip",
-pub async fn get_files_from_layer(data: &[u8], media_type: &str) -> Result<ImageLayerInfo> {
-    let buf;
+pub async fn get_files_from_layer(
+    data: &[u8],
+    media_type: &str,
+    annotations: &HashMap<String, String>,
+) -> Result<ImageLayerInfo> {
     let size = data.len() as u64;
-    let mut unpack_size = size;
-    // TODO 支持gzip zstd等
-    let mut a = if media_type.contains("gzip") {
+    let secret_scanner = get_secret_scanner();
+    // toc无效时解压整个layer
+    let toc_info = get_toc(data, media_type, annotations).unwrap_or_else(|err| {
+        warn!(
+            error = err.to_string(),
+            "parse toc fail, fall back to decompression"
+        );
+        None
+    });
+    let mut layer_toc = None;
+    // 如果有toc，则直接使用toc生成文件列表
+    // 需要检测文件内容或读取包数据库时仍需解压
+    if let Some((toc, toc_data)) = toc_info {
+        let files = get_files_from_toc(&toc_data);
+        let is_binary_content = |item: &ImageFileInfo| {
+            let executable = item.mode.starts_with('-') && item.mode.contains(['x', 's']);
+            is_inventory_file(&item.path, item.size, executable)
+                || is_elf_candidate(&item.path, item.size, executable)
+                || is_ld_config_file(&item.path, item.size)
+        };
+        // elf与语言组件仅在启用了相关检测时才需要解压
+        let need_binary = need_binary_content();
+        let need_content = files.iter().any(|item| {
+            is_package_db_file(&item.path, item.size)
+                || is_account_file(&item.path, item.size)
+                || (need_binary && is_binary_content(item))
+        });
+        if secret_scanner.is_some() || need_content {
+            layer_toc = Some(toc);
+        } else {
+            // 无需解压，解压大小以文件大小汇总
+            let unpack_size = files.iter().map(|item| item.size).sum();
+            // 无文件内容，仅根据路径识别文件类型
+            let mut file_type_list = vec![];
+            for item in files.iter() {
+                if item.is_whiteout.is_some() || !item.mode.starts_with('-') {
+                    continue;
+                }
+                let executable = item.mode.contains(['x', 's']);
+                let file_type = detect_file_type(&item.path, &[], executable);
+                add_file_type_size(&mut file_type_list, file_type, 1, item.size);
+            }
+            file_type_list.sort_by_key(|item| std::cmp::Reverse(item.size));
+            // 未解压，仅计算压缩率
+            let compression = analyze_compression(media_type, size, unpack_size, None, &[], 0);
+            let content_skipped = if files.iter().any(is_binary_content) {
+                Some("elf binaries and language components are not analyzed".to_string())
+            } else {
+                None
+            };
+            return Ok(ImageLayerInfo {
+                size,
+                unpack_size,
+                files,
+                toc: Some(toc),
+                file_type_list,
+                dir_list: get_dirs_from_toc(&toc_data),
+                compression: Some(compression),
+                content_skipped,
+                ..Default::default()
+            });
+        }
+    }
+    let buf;
+    let tar_data: &[u8] = if media_type.contains("gzip") {
         buf = gunzip(data)?;
-        unpack_size = buf.len() as u64;
-        Archive::new(&buf[..])
+        &buf
     } else if media_type.contains("zstd") {
         buf = zstd_decode(data)?;
-        unpack_size = buf.len() as u64;
-        Archive::new(&buf[..])
+        &buf
     } else {
-        Archive::new(data)
+        data
     };
+    let unpack_size = tar_data.len() as u64;
+    // 解压后数据的digest，用于与rootfs.diff_ids对比
+    let diff_id = get_sha256_digest(tar_data);
+    let mut a = Archive::new(tar_data);
 
     let mut files = vec![];
+    let mut secret_list = vec![];
+    let mut package_db_list = vec![];
+    let mut component_list = vec![];
+    let mut elf_binary_list = vec![];
+    let mut ld_config_list = vec![];
+    let mut file_type_list = vec![];
+    let mut dir_list = vec![];
+    let mut account_list = vec![];
+    let mut python_records = HashMap::new();
+    let mut tar_header = TarHeaderAudit::default();
     for file in a.entries().context(TarSnafu {})? {
-        let file = file.context(TarSnafu {})?;
-        let header = file.header();
-        // 不返回目录
-        if header.entry_type().is_dir() {
+        let mut file = file.context(TarSnafu {})?;
+        record_tar_header(&mut tar_header, &mut file)?;
+        // 目录单独返回
+        if file.header().entry_type().is_dir() {
+            let header = file.header();
+            let path = file
+                .path()
+                .context(TarSnafu {})?
+                .to_string_lossy()
+                .to_string();
+            let path = path.trim_end_matches('/').to_string();
+            if !path.is_empty() && path != "." {
+                dir_list.push(ImageFileInfo {
+                    path,
+                    mode: unix_mode::to_string(header.mode().context(TarSnafu {})? | 0o040000),
+                    uid: header.uid().context(TarSnafu {})?,
+                    gid: header.gid().context(TarSnafu {})?,
+                    mtime: header.mtime().unwrap_or_default() as i64,
+                    ..Default::default()
+                });
+            }
             continue;
         }
         let mut link = "".to_string();
@@ -166,38 +572,143 @@ pub async fn get_files_from_layer(data: &[u8], media_type: &str) -> Result<Image
         if let Some(value) = file.link_name().context(TarSnafu {})? {
             link = value.to_string_lossy().to_string()
         }
-        let mut path = file
+        let path = file
             .path()
             .context(TarSnafu {})?
             .to_string_lossy()
             .to_string();
-        let mut is_whiteout = None;
-        // 为了实现这样的删除操作，AuFS 会在可读写层创建一个 whiteout 文件，把只读层里的文件“遮挡”起来。
-        // .wh.
-        // usr/local/bin/.wh.static
-        if let Some(filename) = Path::new(&path).file_name() {
-            let name = filename.to_string_lossy();
-            let prefix = ".wh.";
-            if name.starts_with(prefix) {
-                path = path.replace(name.to_string().as_str(), &name.replace(prefix, ""));
-                is_whiteout = Some(true);
+        // eStargz的toc文件不展示
+        if ESTARGZ_ARTIFACTS.contains(&path.as_str()) {
+            continue;
+        }
+        let (path, is_whiteout) = convert_whiteout(path);
+        let header = file.header();
+        let mut mode = header.mode().context(TarSnafu {})?;
+        // 部分工具生成的tar中mode不包括文件类型，根据entry类型补充
+        if mode & 0o170000 == 0 {
+            let entry_type = header.entry_type();
+            mode |= if entry_type.is_symlink() {
+                0o120000
+            } else if entry_type.is_character_special() {
+                0o020000
+            } else if entry_type.is_block_special() {
+                0o060000
+            } else if entry_type.is_fifo() {
+                0o010000
+            } else {
+                0o100000
+            };
+        }
+        let uid = header.uid().context(TarSnafu {})?;
+        let gid = header.gid().context(TarSnafu {})?;
+        let mtime = header.mtime().unwrap_or_default() as i64;
+        // 普通文件计算内容的hash，用于判断重复文件
+        let mut digest = None;
+        if is_whiteout.is_none() && header.entry_type().is_file() {
+            let mut hasher = Sha256::new();
+            let is_package_db = is_package_db_file(&path, file.size());
+            let executable = mode & 0o111 != 0;
+            // 先读取文件头，用于识别文件类型以及是否为elf文件
+            let mut file_head = vec![];
+            (&mut file)
+                .take(FILE_TYPE_HEADER_SIZE)
+                .read_to_end(&mut file_head)
+                .context(ReadSnafu {})?;
+            // 仅elf文件需要读取全部内容解析，避免读取大的脚本等可执行文件
+            let is_elf_file = is_elf_content(&file_head);
+            let is_inventory = is_inventory_file(&path, file.size(), executable && is_elf_file);
+            let is_elf = is_elf_file && is_elf_candidate(&path, file.size(), executable);
+            let is_ld_config = is_ld_config_file(&path, file.size());
+            let is_account = is_account_file(&path, file.size());
+            let scanner = secret_scanner.filter(|scanner| scanner.should_scan(file.size()));
+            // 需要检测或读取的文件读取内容
+            if is_package_db
+                || is_inventory
+                || is_elf
+                || is_ld_config
+                || is_account
+                || scanner.is_some()
+            {
+                let mut content = file_head;
+                file.read_to_end(&mut content).context(ReadSnafu {})?;
+                hasher.update(&content);
+                if let Some(scanner) = scanner {
+                    secret_list.append(&mut scanner.scan_file(&format!("/{path}"), &content));
+                }
+                if let Some((info, mut components)) = analyze_elf(&path, &content) {
+                    elf_binary_list.push(info);
+                    component_list.append(&mut components);
+                } else if is_inventory {
+                    component_list.append(&mut find_language_components(&path, &content));
+                    if path.ends_with(".dist-info/RECORD") {
+                        python_records.insert(path.clone(), content.clone());
+                    }
+                }
+                if is_package_db {
+                    package_db_list.push(parse_package_db_file(&path, &content));
+                }
+                if is_ld_config {
+                    ld_config_list.push(LdConfigFile {
+                        path: path.clone(),
+                        content: String::from_utf8_lossy(&content).to_string(),
+                    });
+                }
+                if is_account {
+                    account_list.push(AccountFile {
+                        path: path.clone(),
+                        content: String::from_utf8_lossy(&content).to_string(),
+                    });
+                }
+                let file_head = &content[..content.len().min(FILE_TYPE_HEADER_SIZE as usize)];
+                let file_type = detect_file_type(&path, file_head, executable);
+                add_file_type_size(&mut file_type_list, file_type, 1, file.size());
+            } else {
+                hasher.update(&file_head);
+                std::io::copy(&mut file, &mut hasher).context(ReadSnafu {})?;
+                let file_type = detect_file_type(&path, &file_head, executable);
+                add_file_type_size(&mut file_type_list, file_type, 1, file.size());
             }
+            digest = Some(format!("sha256:{}", hex::encode(hasher.finalize())));
         }
-        let mode = header.mode().context(TarSnafu {})?;
         let info = ImageFileInfo {
             path,
             link,
             size: file.size(),
             mode: unix_mode::to_string(mode),
-            uid: header.uid().context(TarSnafu {})?,
-            gid: header.gid().context(TarSnafu {})?,
+            uid,
+            gid,
+            mtime,
             is_whiteout,
+            digest,
         };
         files.push(info);
     }
+    fill_language_component_size(&mut component_list, &files, &python_records);
+    file_type_list.sort_by_key(|item| std::cmp::Reverse(item.size));
+    let compression = analyze_compression(
+        media_type,
+        size,
+        unpack_size,
+        Some(tar_data),
+        &get_zstd_levels(),
+        get_zstd_sample_size(),
+    );
     Ok(ImageLayerInfo {
         files,
         unpack_size,
         size,
+        diff_id: Some(diff_id),
+        toc: layer_toc,
+        secret_list,
+        package_db_list,
+        component_list,
+        elf_binary_list,
+        ld_config_list,
+        file_type_list,
+        dir_list,
+        account_list,
+        tar_header: Some(tar_header),
+        compression: Some(compression),
+        ..Default::default()
     })
 }
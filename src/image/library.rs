@@ -0,0 +1,193 @@
+use glob::Pattern;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use super::{ElfBinaryInfo, MergedFileSystem};
+
+static LD_SO_CONF: &str = "etc/ld.so.conf";
+static LD_SO_CONF_DIR: &str = "etc/ld.so.conf.d/";
+static LD_MUSL_PREFIX: &str = "etc/ld-musl-";
+
+// 配置文件大于此大小则不读取
+static MAX_LD_CONFIG_SIZE: u64 = 64 * 1024;
+
+// 动态链接器默认的搜索目录（glibc、musl以及debian的multiarch目录）
+static DEFAULT_LIBRARY_DIRS: &[&str] = &[
+    "/lib",
+    "/usr/lib",
+    "/lib64",
+    "/usr/lib64",
+    "/usr/local/lib",
+    "/lib/x86_64-linux-gnu",
+    "/usr/lib/x86_64-linux-gnu",
+    "/lib/aarch64-linux-gnu",
+    "/usr/lib/aarch64-linux-gnu",
+    "/lib/arm-linux-gnueabihf",
+    "/usr/lib/arm-linux-gnueabihf",
+    "/lib/i386-linux-gnu",
+    "/usr/lib/i386-linux-gnu",
+    "/lib/powerpc64le-linux-gnu",
+    "/usr/lib/powerpc64le-linux-gnu",
+    "/lib/s390x-linux-gnu",
+    "/usr/lib/s390x-linux-gnu",
+];
+
+// layer中动态链接器的配置文件
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LdConfigFile {
+    pub path: String,
+    pub content: String,
+}
+
+// 是否需要读取的动态链接器配置文件
+pub fn is_ld_config_file(path: &str, size: u64) -> bool {
+    if size == 0 || size > MAX_LD_CONFIG_SIZE {
+        return false;
+    }
+    path == LD_SO_CONF
+        || path.starts_with(LD_SO_CONF_DIR)
+        || (path.starts_with(LD_MUSL_PREFIX) && path.ends_with(".path"))
+}
+
+// 解析ld.so.conf，include的文件以glob匹配
+fn parse_ld_so_conf(
+    content: &str,
+    configs: &HashMap<&str, &str>,
+    visited: &mut HashSet<String>,
+    dirs: &mut Vec<String>,
+) {
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() || line.starts_with("hwcap ") {
+            continue;
+        }
+        let Some(value) = line.strip_prefix("include ") else {
+            dirs.extend(
+                line.split([':', ',', ' ', '\t'])
+                    .filter(|item| !item.is_empty())
+                    .map(|item| item.to_string()),
+            );
+            continue;
+        };
+        for pattern in value.split_whitespace() {
+            // 相对路径相对于/etc
+            let pattern = if pattern.starts_with('/') {
+                pattern.trim_start_matches('/').to_string()
+            } else {
+                format!("etc/{pattern}")
+            };
+            let Ok(glob) = Pattern::new(&pattern) else {
+                continue;
+            };
+            let mut paths: Vec<&&str> = configs.keys().filter(|path| glob.matches(path)).collect();
+            paths.sort();
+            for path in paths {
+                if visited.insert(path.to_string()) {
+                    parse_ld_so_conf(configs[*path], configs, visited, dirs);
+                }
+            }
+        }
+    }
+}
+
+// 动态库的搜索目录：ld.so.conf、musl的path文件以及默认目录
+fn get_library_dirs(configs: &[LdConfigFile]) -> Vec<String> {
+    let configs: HashMap<&str, &str> = configs
+        .iter()
+        .map(|item| (item.path.as_str(), item.content.as_str()))
+        .collect();
+    let mut dirs = vec![];
+    if let Some(content) = configs.get(LD_SO_CONF) {
+        let mut visited = HashSet::new();
+        parse_ld_so_conf(content, &configs, &mut visited, &mut dirs);
+    }
+    for (path, content) in configs.iter() {
+        if path.starts_with(LD_MUSL_PREFIX) {
+            dirs.extend(
+                content
+                    .split([':', '\n'])
+                    .map(|item| item.trim().to_string())
+                    .filter(|item| !item.is_empty()),
+            );
+        }
+    }
+    dirs.extend(DEFAULT_LIBRARY_DIRS.iter().map(|item| item.to_string()));
+    dirs
+}
+
+// 二进制中rpath与runpath的目录，$ORIGIN替换为二进制所在目录
+fn get_runpath_dirs(binary: &ElfBinaryInfo) -> Vec<String> {
+    let origin = binary
+        .path
+        .rsplit_once('/')
+        .map(|(dir, _)| dir)
+        .unwrap_or_default();
+    binary
+        .runpath
+        .iter()
+        .flat_map(|item| item.split(':'))
+        .filter(|item| !item.is_empty())
+        .map(|item| item.replace("${ORIGIN}", origin).replace("$ORIGIN", origin))
+        .collect()
+}
+
+// 查找动态库，返回其最终指向的文件路径
+fn find_library(fs: &MergedFileSystem, dirs: &[String], name: &str) -> Option<String> {
+    if name.contains('/') {
+        return fs.resolve(name).map(|file| format!("/{}", file.info.path));
+    }
+    dirs.iter().find_map(|dir| {
+        fs.resolve(&format!("{}/{name}", dir.trim_end_matches('/')))
+            .map(|file| format!("/{}", file.info.path))
+    })
+}
+
+// 检测动态链接的可执行文件的动态链接器与依赖的动态库（包括间接依赖）是否存在
+// 返回无法找到的依赖，格式为：可执行文件: 依赖（间接依赖时记录需要它的动态库）
+pub fn find_missing_libraries(
+    fs: &MergedFileSystem,
+    binaries: &[ElfBinaryInfo],
+    configs: &[LdConfigFile],
+) -> Vec<String> {
+    let library_dirs = get_library_dirs(configs);
+    let binary_map: HashMap<&str, &ElfBinaryInfo> = binaries
+        .iter()
+        .map(|item| (item.path.as_str(), item))
+        .collect();
+    let mut missing_list = vec![];
+    // 有动态链接器的为可执行文件
+    for executable in binaries.iter().filter(|item| !item.interpreter.is_empty()) {
+        if find_library(fs, &[], &executable.interpreter).is_none() {
+            missing_list.push(format!(
+                "{}: {} (interpreter)",
+                executable.path, executable.interpreter
+            ));
+        }
+        let mut visited = HashSet::new();
+        let mut pending = vec![executable];
+        while let Some(binary) = pending.pop() {
+            let mut dirs = get_runpath_dirs(binary);
+            dirs.extend(library_dirs.iter().cloned());
+            for name in binary.needed.iter() {
+                if !visited.insert(name.as_str()) {
+                    continue;
+                }
+                let Some(path) = find_library(fs, &dirs, name) else {
+                    if binary.path == executable.path {
+                        missing_list.push(format!("{}: {name}", executable.path));
+                    } else {
+                        missing_list.push(format!(
+                            "{}: {name} (needed by {})",
+                            executable.path, binary.path
+                        ));
+                    }
+                    continue;
+                };
+                if let Some(library) = binary_map.get(path.as_str()) {
+                    pending.push(*library);
+                }
+            }
+        }
+    }
+    missing_list
+}
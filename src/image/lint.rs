@@ -0,0 +1,161 @@
+use bytesize::ByteSize;
+use glob::{MatchOptions, Pattern};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use super::ImageMergedFile;
+use crate::config::{LintRule, LintSeverity};
+
+// 每条规则最多记录的文件数
+static MAX_PATH_COUNT: usize = 100;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintIssue {
+    // 规则名称
+    pub rule: String,
+    // 严重级别：error或warning
+    pub severity: String,
+    pub message: String,
+    // 不符合规则的文件（最多记录100个）
+    pub paths: Vec<String>,
+    // 不符合规则的文件总数
+    pub count: usize,
+}
+
+impl LintIssue {
+    pub fn is_error(&self) -> bool {
+        self.severity == LintSeverity::Error.as_str()
+    }
+}
+
+// 是否root用户运行，未设置用户时默认为root
+pub fn is_root_user(user: &str) -> bool {
+    let name = user.split(':').next().unwrap_or_default();
+    name.is_empty() || name == "root" || name == "0"
+}
+
+// 是否设置了setuid或setgid
+fn is_setuid(mode: &str) -> bool {
+    let chars: Vec<char> = mode.chars().collect();
+    [3, 6]
+        .iter()
+        .any(|index| matches!(chars.get(*index), Some('s' | 'S')))
+}
+
+// 最终文件系统中的所有目录，包括tar中未单独记录的父目录
+fn get_dir_paths<'a>(
+    files: &'a [ImageMergedFile],
+    dirs: &'a [ImageMergedFile],
+) -> BTreeSet<&'a str> {
+    let mut dir_paths: BTreeSet<&str> = dirs.iter().map(|item| item.info.path.as_str()).collect();
+    for file in files.iter() {
+        let mut path = file.info.path.as_str();
+        while let Some((dir, _)) = path.rsplit_once('/') {
+            dir_paths.insert(dir);
+            path = dir;
+        }
+    }
+    dir_paths
+}
+
+// 根据规则检测镜像的最终文件系统与运行用户
+// dirs为最终文件系统中的目录，missing_libraries为无法找到的动态库依赖
+pub fn lint_image(
+    rules: &[LintRule],
+    files: &[ImageMergedFile],
+    dirs: &[ImageMergedFile],
+    user: &str,
+    missing_libraries: &[String],
+) -> Vec<LintIssue> {
+    let mut issues = vec![];
+    let mut dir_paths = None;
+    for rule in rules.iter() {
+        let severity = rule.severity.unwrap_or_default();
+        if severity == LintSeverity::Off {
+            continue;
+        }
+        let name = rule.name.clone().unwrap_or_else(|| rule.kind.clone());
+        let mut issue = LintIssue {
+            rule: name,
+            severity: severity.as_str().to_string(),
+            ..Default::default()
+        };
+        let mut paths = vec![];
+        match rule.kind.as_str() {
+            "path_not_exist" => {
+                let pattern = rule.pattern.clone().unwrap_or_default();
+                let Ok(glob) = Pattern::new(pattern.trim_start_matches('/')) else {
+                    issue.message = format!("invalid pattern {pattern}");
+                    issues.push(issue);
+                    continue;
+                };
+                let options = MatchOptions {
+                    require_literal_separator: true,
+                    ..Default::default()
+                };
+                for file in files.iter() {
+                    if glob.matches_with(&file.info.path, options) {
+                        paths.push(format!("/{}", file.info.path));
+                    }
+                }
+                // 目录同样需要匹配，如：**/.git
+                for dir in dir_paths
+                    .get_or_insert_with(|| get_dir_paths(files, dirs))
+                    .iter()
+                {
+                    if glob.matches_with(dir, options) {
+                        paths.push(format!("/{dir}/"));
+                    }
+                }
+                issue.message = format!("path {pattern} must not exist");
+            }
+            "max_file_size" => {
+                let Some(size) = rule.size.map(|item| item.0) else {
+                    issue.message = "size of max_file_size is missing".to_string();
+                    issues.push(issue);
+                    continue;
+                };
+                for file in files.iter() {
+                    if file.info.size > size {
+                        paths.push(format!("/{}", file.info.path));
+                    }
+                }
+                issue.message = format!("file must not be larger than {}", ByteSize(size));
+            }
+            "no_setuid" => {
+                for file in files.iter() {
+                    if !file.info.mode.starts_with('d') && is_setuid(&file.info.mode) {
+                        paths.push(format!("/{}", file.info.path));
+                    }
+                }
+                issue.message = "file must not have setuid or setgid bit".to_string();
+            }
+            "shared_library" => {
+                paths = missing_libraries.to_vec();
+                issue.message = "shared library or interpreter must be resolvable".to_string();
+            }
+            "non_root_user" => {
+                if is_root_user(user) {
+                    issue.message = "user must not be root".to_string();
+                    issue.count = 1;
+                    issues.push(issue);
+                }
+                continue;
+            }
+            _ => {
+                issue.message = format!("unknown rule kind {}", rule.kind);
+                issues.push(issue);
+                continue;
+            }
+        }
+        if paths.is_empty() {
+            continue;
+        }
+        issue.count = paths.len();
+        paths.truncate(MAX_PATH_COUNT);
+        issue.paths = paths;
+        issues.push(issue);
+    }
+    issues
+}
@@ -1,16 +1,64 @@
+mod cache;
+mod compression;
+mod config_check;
+mod diff;
 mod docker;
+mod duplicate;
+mod elf;
+mod filesystem;
+mod filetype;
+mod ghost;
+mod inventory;
 mod layer;
+mod library;
+mod lint;
 mod oci_image;
+mod permission;
+mod reproducible;
+mod sbom;
+mod secret;
+mod vulnerability;
 
+pub use cache::{find_package_caches, PackageCacheInfo};
+pub use compression::{analyze_compression, summarize_zstd_savings, LayerCompression, ZstdSavings};
+pub use config_check::{check_image_config, ConfigCheckOption};
+pub use diff::{diff_image, ImageDiffFile, ImageDiffResult, ImageMetadataDiff};
 pub use docker::{
-    analyze_docker_image, parse_image_info, DockerAnalyzeResult, DockerAnalyzeSummary,
+    analyze_docker_image, parse_image_info, BaseImageInfo, DiffIdMismatchInfo, DockerAnalyzeResult,
+    DockerAnalyzeSummary,
+};
+pub use duplicate::{find_duplicate_files, DuplicateFileGroup};
+pub use elf::{analyze_elf, is_elf_candidate, is_elf_content, ElfBinaryInfo};
+pub use filesystem::MergedFileSystem;
+pub use filetype::{add_file_type_size, detect_file_type, FileTypeSize, FILE_TYPE_HEADER_SIZE};
+pub use ghost::{find_ghost_files, GhostFileInfo};
+pub use inventory::{
+    fill_language_component_size, find_elf_components, find_language_components, is_inventory_file,
+    LanguageComponent,
 };
 pub use layer::{
     get_file_content_from_layer, get_file_content_from_tar, get_file_size_from_tar,
-    get_files_from_layer,
+    get_files_from_layer, get_sha256_digest,
 };
+pub use library::{find_missing_libraries, is_ld_config_file, LdConfigFile};
+pub use lint::{is_root_user, lint_image, LintIssue};
 pub use oci_image::{
-    convert_files_to_file_tree, find_file_tree_item, FileTreeItem, ImageConfig, ImageFileInfo,
-    ImageIndex, ImageLayer, ImageManifest, ImageManifestConfig, Op,
+    build_merged_file_tree, convert_files_to_file_tree, diff_file_tree, find_file_tree_item,
+    is_encrypted_layer, is_foreign_layer, FileTreeItem, ImageConfig, ImageExtraInfo, ImageFileInfo,
+    ImageHealthcheck, ImageHistory, ImageIndex, ImageLayer, ImageLayerToc, ImageManifest,
+    ImageManifestConfig, ImageMergedFile, Op, ANNOTATION_BASE_DIGEST, ANNOTATION_BASE_NAME,
     MEDIA_TYPE_DOCKER_SCHEMA2_MANIFEST, MEDIA_TYPE_IMAGE_INDEX, MEDIA_TYPE_MANIFEST_LIST,
 };
+pub use permission::{
+    audit_permissions, get_account_ids, is_account_file, AccountFile, PermissionAuditOption,
+    PermissionFinding,
+};
+pub use reproducible::{
+    audit_reproducibility, ReproducibilityOption, ReproducibilityReport, TarHeaderAudit,
+};
+pub use sbom::{
+    build_sbom, is_package_db_file, parse_package_db_file, to_sbom_document, ImageSbom,
+    PackageDbFile,
+};
+pub use secret::{find_env_secrets, get_secret_scanner, SecretFinding};
+pub use vulnerability::{find_vulnerabilities, get_osv_database, VulnerabilityFinding};
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+use super::{FileTypeSize, LayerCompression, MergedFileSystem};
 
 pub static MEDIA_TYPE_IMAGE_INDEX: &str = "application/vnd.oci.image.index.v1+json";
 
@@ -9,6 +11,28 @@ pub static MEDIA_TYPE_DOCKER_SCHEMA2_MANIFEST: &str =
 pub static MEDIA_TYPE_MANIFEST_LIST: &str =
     "application/vnd.docker.distribution.manifest.list.v2+json";
 
+// windows基础镜像等使用的外部layer，数据不在registry中
+static MEDIA_TYPE_FOREIGN_LAYER: &str = "application/vnd.docker.image.rootfs.foreign.diff.tar";
+static MEDIA_TYPE_NONDISTRIBUTABLE_LAYER: &str =
+    "application/vnd.oci.image.layer.nondistributable.v1.tar";
+// ocicrypt加密的layer
+static MEDIA_TYPE_ENCRYPTED_SUFFIX: &str = "+encrypted";
+
+// 构建时声明的基础镜像
+pub static ANNOTATION_BASE_NAME: &str = "org.opencontainers.image.base.name";
+pub static ANNOTATION_BASE_DIGEST: &str = "org.opencontainers.image.base.digest";
+
+// 是否外部（不可分发）的layer
+pub fn is_foreign_layer(media_type: &str) -> bool {
+    media_type.starts_with(MEDIA_TYPE_FOREIGN_LAYER)
+        || media_type.starts_with(MEDIA_TYPE_NONDISTRIBUTABLE_LAYER)
+}
+
+// 是否加密的layer
+pub fn is_encrypted_layer(media_type: &str) -> bool {
+    media_type.ends_with(MEDIA_TYPE_ENCRYPTED_SUFFIX)
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ImageFileInfo {
@@ -22,8 +46,12 @@ pub struct ImageFileInfo {
     pub mode: String,
     pub uid: u64,
     pub gid: u64,
+    // 修改时间（unix时间戳）
+    pub mtime: i64,
     // 该文件是否对应删除
     pub is_whiteout: Option<bool>,
+    // 文件内容的sha256
+    pub digest: Option<String>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -32,6 +60,14 @@ pub struct ImageLayer {
     // 创建时间
     pub created: String,
     pub digest: String,
+    // 解压后内容的digest（对应rootfs.diff_ids）
+    pub diff_id: String,
+    // 解压后内容的digest是否与diff_id一致（未校验则为空）
+    pub diff_id_verified: Option<bool>,
+    // 未校验diff id的原因（使用toc未解压或缺少diff id）
+    pub diff_id_unverified: Option<String>,
+    // 使用toc未解压时，跳过的文件内容分析
+    pub content_skipped: Option<String>,
     // 创建该层的命令
     pub cmd: String,
     // layer的大小
@@ -42,6 +78,33 @@ pub struct ImageLayer {
     pub unpack_size: u64,
     // 该层是否为空（无文件操作）
     pub empty: bool,
+    // eStargz或zstd:chunked的toc信息
+    pub toc: Option<ImageLayerToc>,
+    // 无法分析的原因（加密或外部layer），此时只有声明的大小
+    pub unsupported: Option<String>,
+    // 解析失败的出错信息
+    pub error: Option<String>,
+    // 是否属于基础镜像
+    pub base: bool,
+    // 按文件类型汇总的大小
+    pub file_type_list: Vec<FileTypeSize>,
+    // 压缩率与zstd压缩大小的估算（无法分析时为空）
+    pub compression: Option<LayerCompression>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageLayerToc {
+    // 格式：estargz或zstd:chunked
+    pub format: String,
+    // toc的digest
+    pub digest: String,
+    // toc的大小
+    pub size: u64,
+    // 文件数（不包括分块）
+    pub entry_count: usize,
+    // 分块数
+    pub chunk_count: usize,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -55,6 +118,15 @@ pub struct ImageFileSummary {
     pub info: ImageFileInfo,
 }
 
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageMergedFile {
+    // 最后修改该文件的层
+    pub layer_index: usize,
+    // 文件信息
+    pub info: ImageFileInfo,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ImageIndex {
@@ -136,6 +208,7 @@ pub struct ImageManifest {
     pub config: ImageManifestConfig,
     // 文件分层信息
     pub layers: Vec<ImageManifestLayer>,
+    pub annotations: Option<HashMap<String, String>>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -152,17 +225,79 @@ pub struct ImageManifestLayer {
     pub media_type: String,
     pub digest: String,
     pub size: u64,
+    pub annotations: Option<HashMap<String, String>>,
+    // 外部layer的下载地址
+    pub urls: Option<Vec<String>>,
+}
+
+// 镜像的健康检查，时间均为纳秒
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageHealthcheck {
+    // 检测命令，["NONE"]表示禁用
+    #[serde(rename = "Test", default)]
+    pub test: Vec<String>,
+    #[serde(rename = "Interval")]
+    pub interval: Option<i64>,
+    #[serde(rename = "Timeout")]
+    pub timeout: Option<i64>,
+    #[serde(rename = "StartPeriod")]
+    pub start_period: Option<i64>,
+    #[serde(rename = "StartInterval")]
+    pub start_interval: Option<i64>,
+    #[serde(rename = "Retries")]
+    pub retries: Option<i64>,
 }
 
+// 镜像的运行配置（config字段），字段名与docker一致
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ImageExtraInfo {
     #[serde(rename = "User")]
     pub user: Option<String>,
+    // 暴露的端口，如80/tcp
+    #[serde(rename = "ExposedPorts")]
+    pub exposed_ports: Option<BTreeMap<String, serde_json::Value>>,
     #[serde(rename = "Env")]
     pub env: Option<Vec<String>>,
+    #[serde(rename = "Entrypoint")]
+    pub entrypoint: Option<Vec<String>>,
+    #[serde(rename = "Cmd")]
+    pub cmd: Option<Vec<String>>,
+    #[serde(rename = "Volumes")]
+    pub volumes: Option<BTreeMap<String, serde_json::Value>>,
+    #[serde(rename = "WorkingDir")]
+    pub working_dir: Option<String>,
     #[serde(rename = "Labels")]
     pub labels: Option<HashMap<String, String>>,
+    #[serde(rename = "StopSignal")]
+    pub stop_signal: Option<String>,
+    #[serde(rename = "OnBuild")]
+    pub on_build: Option<Vec<String>>,
+    #[serde(rename = "Healthcheck")]
+    pub healthcheck: Option<ImageHealthcheck>,
+    // RUN等使用的shell
+    #[serde(rename = "Shell")]
+    pub shell: Option<Vec<String>>,
+    #[serde(rename = "ArgsEscaped")]
+    pub args_escaped: Option<bool>,
+}
+
+impl ImageExtraInfo {
+    // 暴露的端口列表
+    pub fn get_exposed_ports(&self) -> Vec<String> {
+        self.exposed_ports
+            .as_ref()
+            .map(|ports| ports.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+    // 挂载的卷列表
+    pub fn get_volumes(&self) -> Vec<String> {
+        self.volumes
+            .as_ref()
+            .map(|volumes| volumes.keys().cloned().collect())
+            .unwrap_or_default()
+    }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -171,10 +306,13 @@ pub struct ImageConfig {
     // 架构
     pub architecture: String,
     // 创建时间
+    #[serde(default)]
     pub created: String,
-    // 历史记录
+    // 历史记录（ko、nix等构建的镜像可能为空）
+    #[serde(default)]
     pub history: Vec<ImageHistory>,
     pub os: String,
+    #[serde(default)]
     pub rootfs: ImageRootfs,
     // 镜像信息(还有其它更多字段未读取)
     pub config: Option<ImageExtraInfo>,
@@ -183,6 +321,7 @@ pub struct ImageConfig {
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ImageHistory {
+    #[serde(default)]
     pub created: String,
     #[serde(rename = "created_by")]
     pub created_by: Option<String>,
@@ -193,9 +332,9 @@ pub struct ImageHistory {
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ImageRootfs {
-    #[serde(rename = "type")]
+    #[serde(rename = "type", default)]
     pub type_field: String,
-    #[serde(rename = "diff_ids")]
+    #[serde(rename = "diff_ids", default)]
     pub diff_ids: Vec<String>,
 }
 
@@ -224,6 +363,8 @@ pub struct FileTreeItem {
     pub gid: u64,
     // 操作：删除、更新等
     pub op: Op,
+    // 最后修改该文件的层（仅合并后的文件树有此值）
+    pub layer_index: Option<usize>,
     // 子文件
     pub children: Vec<FileTreeItem>,
 }
@@ -260,6 +401,7 @@ fn add_file(items: &mut Vec<FileTreeItem>, name_list: Vec<&str>, item: FileTreeI
     for (index, dir) in items.iter_mut().enumerate() {
         if dir.name == name {
             dir.size += item.size;
+            dir.layer_index = dir.layer_index.max(item.layer_index);
             found_index = index as i64;
         }
     }
@@ -273,6 +415,7 @@ fn add_file(items: &mut Vec<FileTreeItem>, name_list: Vec<&str>, item: FileTreeI
         items.push(FileTreeItem {
             name: name.to_string(),
             size: item.size,
+            layer_index: item.layer_index,
             op,
             // TODO 其它属性
             ..Default::default()
@@ -324,3 +467,111 @@ pub fn convert_files_to_file_tree(
     }
     file_tree
 }
+
+// 将合并后的文件转换为文件树，当前层修改的文件标记为新增
+pub fn convert_merged_files_to_file_tree(
+    files: &[ImageMergedFile],
+    current_layer_index: usize,
+) -> Vec<FileTreeItem> {
+    let mut file_tree: Vec<FileTreeItem> = vec![];
+    for file in files.iter() {
+        let info = &file.info;
+        let arr: Vec<&str> = info.path.split('/').collect();
+        if arr.is_empty() {
+            continue;
+        }
+        let mut op = Op::None;
+        if file.layer_index == current_layer_index {
+            op = Op::Added;
+        }
+        let size = arr.len();
+        add_file(
+            &mut file_tree,
+            arr[0..size - 1].to_vec(),
+            FileTreeItem {
+                name: arr[size - 1].to_string(),
+                link: info.link.clone(),
+                size: info.size,
+                mode: info.mode.clone(),
+                uid: info.uid,
+                gid: info.gid,
+                op,
+                layer_index: Some(file.layer_index),
+                ..Default::default()
+            },
+        )
+    }
+    file_tree
+}
+
+// 按顺序叠加各层的文件，生成叠加至该层后的文件树
+// 仅在需要时生成，避免保存每一层完整的文件树
+pub fn build_merged_file_tree(
+    layer_file_list: &[Vec<ImageFileInfo>],
+    layer_index: usize,
+) -> Vec<FileTreeItem> {
+    let mut merged_file_system = MergedFileSystem::default();
+    for (index, files) in layer_file_list.iter().enumerate().take(layer_index + 1) {
+        merged_file_system.apply(index, files);
+    }
+    convert_merged_files_to_file_tree(&merged_file_system.files(), layer_index)
+}
+
+// 获取文件树中的所有文件（不包括有子文件的目录）
+fn flatten_file_tree(
+    items: &[FileTreeItem],
+    prefix: &str,
+    files: &mut BTreeMap<String, FileTreeItem>,
+) {
+    for item in items.iter() {
+        let path = if prefix.is_empty() {
+            item.name.clone()
+        } else {
+            format!("{prefix}/{}", item.name)
+        };
+        if item.children.is_empty() {
+            files.insert(path, item.clone());
+        } else {
+            flatten_file_tree(&item.children, &path, files);
+        }
+    }
+}
+
+// 对比两个叠加后的文件树，生成包含新增、修改与删除文件的文件树
+pub fn diff_file_tree(before: &[FileTreeItem], after: &[FileTreeItem]) -> Vec<FileTreeItem> {
+    let mut before_files = BTreeMap::new();
+    flatten_file_tree(before, "", &mut before_files);
+    let mut after_files = BTreeMap::new();
+    flatten_file_tree(after, "", &mut after_files);
+
+    let mut files = vec![];
+    for (path, item) in after_files.iter() {
+        let op = match before_files.get(path) {
+            None => Op::Added,
+            // 最后修改的层不一致，则表示该文件在后续层被重新写入
+            Some(value) if value.layer_index != item.layer_index || value.size != item.size => {
+                Op::Modified
+            }
+            _ => continue,
+        };
+        files.push((path, item, op));
+    }
+    for (path, item) in before_files.iter() {
+        if !after_files.contains_key(path) {
+            files.push((path, item, Op::Removed));
+        }
+    }
+    files.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut file_tree: Vec<FileTreeItem> = vec![];
+    for (path, item, op) in files {
+        let arr: Vec<&str> = path.split('/').collect();
+        let size = arr.len();
+        add_file(
+            &mut file_tree,
+            arr[0..size - 1].to_vec(),
+            FileTreeItem { op, ..item.clone() },
+        )
+    }
+    file_tree
+}
@@ -0,0 +1,285 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use super::{ImageFileInfo, ImageMergedFile, MergedFileSystem};
+
+static ETC_PASSWD: &str = "etc/passwd";
+static ETC_GROUP: &str = "etc/group";
+
+// 账号文件大于此大小则不读取
+static MAX_ACCOUNT_FILE_SIZE: u64 = 1024 * 1024;
+
+// 每种问题最多记录的文件数
+static MAX_FINDING_COUNT: usize = 100;
+
+// 未设置PATH时的默认值
+static DEFAULT_PATH: &str = "/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin";
+
+static PERMISSION_SETUID: &str = "setuid";
+static PERMISSION_SETGID: &str = "setgid";
+static PERMISSION_WORLD_WRITABLE: &str = "world writable";
+static PERMISSION_WORLD_WRITABLE_DIR: &str = "world writable dir without sticky bit";
+static PERMISSION_UNKNOWN_UID: &str = "unknown uid";
+static PERMISSION_UNKNOWN_GID: &str = "unknown gid";
+static PERMISSION_WRITABLE_IN_PATH: &str = "writable in PATH";
+static PERMISSION_NOT_ACCESSIBLE: &str = "not accessible by user";
+
+// /etc/passwd或/etc/group
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountFile {
+    pub path: String,
+    pub content: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionFinding {
+    // 问题类型
+    pub kind: String,
+    pub path: String,
+    pub mode: String,
+    pub uid: u64,
+    pub gid: u64,
+    // 最后修改该文件的layer
+    pub layer_index: usize,
+}
+
+pub struct PermissionAuditOption<'a> {
+    // 最终文件系统
+    pub fs: &'a MergedFileSystem,
+    // 最终文件系统的目录
+    pub dirs: &'a [ImageMergedFile],
+    // 最终文件系统的/etc/passwd与/etc/group
+    pub accounts: &'a [AccountFile],
+    // 运行用户
+    pub user: &'a str,
+    pub envs: &'a [String],
+    // entrypoint与cmd
+    pub command: &'a [String],
+    pub working_dir: &'a str,
+}
+
+// 是否需要读取的账号文件
+pub fn is_account_file(path: &str, size: u64) -> bool {
+    size > 0 && size <= MAX_ACCOUNT_FILE_SIZE && (path == ETC_PASSWD || path == ETC_GROUP)
+}
+
+// 账号文件中的uid与gid
+pub fn get_account_ids(accounts: &[AccountFile]) -> (HashSet<u64>, HashSet<u64>) {
+    let ids = |path: &str| -> HashSet<u64> {
+        accounts
+            .iter()
+            .filter(|item| item.path == path)
+            .flat_map(|item| parse_accounts(&item.content))
+            .map(|(_, id, _)| id)
+            .collect()
+    };
+    (ids(ETC_PASSWD), ids(ETC_GROUP))
+}
+
+// 运行用户的uid与所属的gid
+struct RunUser {
+    uid: u64,
+    gids: Vec<u64>,
+}
+
+// 解析passwd或group，返回名称与id（以及passwd中的gid）
+fn parse_accounts(content: &str) -> Vec<(String, u64, Option<u64>)> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(':').collect();
+            let id = fields.get(2)?.parse::<u64>().ok()?;
+            let gid = fields.get(3).and_then(|item| item.parse::<u64>().ok());
+            Some((fields[0].to_string(), id, gid))
+        })
+        .collect()
+}
+
+// 根据User（name、uid、name:group、uid:gid）获取运行用户
+fn get_run_user(
+    user: &str,
+    users: &[(String, u64, Option<u64>)],
+    groups: &[(String, u64, Option<u64>)],
+) -> Option<RunUser> {
+    let (name, group) = user.split_once(':').unwrap_or((user, ""));
+    if name.is_empty() {
+        return Some(RunUser {
+            uid: 0,
+            gids: vec![0],
+        });
+    }
+    let account = users
+        .iter()
+        .find(|(item, id, _)| item == name || id.to_string() == name);
+    let uid = match account {
+        Some((_, id, _)) => *id,
+        None => name.parse::<u64>().ok()?,
+    };
+    let mut gids = vec![];
+    if group.is_empty() {
+        if let Some(gid) = account.and_then(|(_, _, gid)| *gid) {
+            gids.push(gid);
+        }
+    } else if let Some((_, gid, _)) = groups
+        .iter()
+        .find(|(item, id, _)| item == group || id.to_string() == group)
+    {
+        gids.push(*gid);
+    } else if let Ok(gid) = group.parse::<u64>() {
+        gids.push(gid);
+    }
+    Some(RunUser { uid, gids })
+}
+
+// 用户是否有文件的权限，index为r、w、x在mode中的偏移（1、2、3）
+fn has_permission(file: &ImageFileInfo, user: &RunUser, index: usize) -> bool {
+    if user.uid == 0 {
+        return true;
+    }
+    let chars: Vec<char> = file.mode.chars().collect();
+    let offset = if file.uid == user.uid {
+        0
+    } else if user.gids.contains(&file.gid) {
+        3
+    } else {
+        6
+    };
+    matches!(chars.get(offset + index), Some('r' | 'w' | 'x' | 's' | 't'))
+}
+
+fn is_world_writable(mode: &str) -> bool {
+    mode.chars().nth(8) == Some('w')
+}
+
+fn new_finding(kind: &str, file: &ImageMergedFile) -> PermissionFinding {
+    PermissionFinding {
+        kind: kind.to_string(),
+        path: format!("/{}", file.info.path),
+        mode: file.info.mode.clone(),
+        uid: file.info.uid,
+        gid: file.info.gid,
+        layer_index: file.layer_index,
+    }
+}
+
+// 检测最终文件系统中文件的权限与属主
+pub fn audit_permissions(opt: PermissionAuditOption) -> Vec<PermissionFinding> {
+    let mut findings = vec![];
+    let content = |path: &str| {
+        opt.accounts
+            .iter()
+            .find(|item| item.path == path)
+            .map(|item| item.content.as_str())
+    };
+    let passwd = content(ETC_PASSWD);
+    let users = parse_accounts(passwd.unwrap_or_default());
+    let groups = parse_accounts(content(ETC_GROUP).unwrap_or_default());
+    let uids: HashSet<u64> = users.iter().map(|(_, id, _)| *id).collect();
+    let gids: HashSet<u64> = groups.iter().map(|(_, id, _)| *id).collect();
+    let run_user = get_run_user(opt.user, &users, &groups);
+
+    let path_value = opt
+        .envs
+        .iter()
+        .find_map(|item| item.strip_prefix("PATH="))
+        .unwrap_or(DEFAULT_PATH);
+    let path_dirs: HashSet<&str> = path_value
+        .split(':')
+        .map(|item| item.trim_matches('/'))
+        .filter(|item| !item.is_empty())
+        .collect();
+
+    let files = opt.fs.files();
+    for file in files.iter().chain(opt.dirs.iter()) {
+        let mode = &file.info.mode;
+        let is_dir = mode.starts_with('d');
+        // 符号链接的权限无意义
+        if mode.starts_with('l') {
+            continue;
+        }
+        let chars: Vec<char> = mode.chars().collect();
+        if !is_dir {
+            if matches!(chars.get(3), Some('s' | 'S')) {
+                findings.push(new_finding(PERMISSION_SETUID, file));
+            }
+            if matches!(chars.get(6), Some('s' | 'S')) {
+                findings.push(new_finding(PERMISSION_SETGID, file));
+            }
+        }
+        if is_world_writable(mode) {
+            if !is_dir {
+                findings.push(new_finding(PERMISSION_WORLD_WRITABLE, file));
+            } else if !matches!(chars.get(9), Some('t' | 'T')) {
+                findings.push(new_finding(PERMISSION_WORLD_WRITABLE_DIR, file));
+            }
+        }
+        // 无passwd（如scratch镜像）时不检测属主
+        if passwd.is_some() && !uids.contains(&file.info.uid) {
+            findings.push(new_finding(PERMISSION_UNKNOWN_UID, file));
+        }
+        if !gids.is_empty() && !gids.contains(&file.info.gid) {
+            findings.push(new_finding(PERMISSION_UNKNOWN_GID, file));
+        }
+        // PATH中的目录或文件可被运行用户修改（root仅检测所有人可写）
+        let in_path = if is_dir {
+            path_dirs.contains(file.info.path.as_str())
+        } else {
+            let dir = file.info.path.rsplit_once('/').map(|(dir, _)| dir);
+            dir.is_some_and(|dir| path_dirs.contains(dir))
+        };
+        if in_path {
+            let writable = match run_user {
+                Some(ref user) if user.uid != 0 => has_permission(&file.info, user, 2),
+                _ => is_world_writable(mode),
+            };
+            if writable {
+                findings.push(new_finding(PERMISSION_WRITABLE_IN_PATH, file));
+            }
+        }
+    }
+
+    // 非root用户运行时，检测entrypoint与其参数中的文件是否可访问
+    if let Some(ref user) = run_user.filter(|user| user.uid != 0) {
+        let mut checked = HashSet::new();
+        for (index, arg) in opt.command.iter().enumerate() {
+            let candidates = if arg.starts_with('/') {
+                vec![arg.clone()]
+            } else if index == 0 && !arg.contains('/') {
+                // 可执行文件从PATH中查找
+                path_value
+                    .split(':')
+                    .map(|dir| format!("{}/{arg}", dir.trim_end_matches('/')))
+                    .collect()
+            } else if arg.contains('/') || arg.contains('.') {
+                vec![format!("{}/{arg}", opt.working_dir.trim_end_matches('/'))]
+            } else {
+                continue;
+            };
+            let Some(file) = candidates.iter().find_map(|path| opt.fs.resolve(path)) else {
+                continue;
+            };
+            if !checked.insert(file.info.path.clone()) {
+                continue;
+            }
+            // 可执行文件需要执行权限，参数中的文件需要读权限
+            let accessible = if index == 0 {
+                has_permission(&file.info, user, 3)
+            } else {
+                has_permission(&file.info, user, 1)
+            };
+            if !accessible {
+                findings.push(new_finding(PERMISSION_NOT_ACCESSIBLE, file));
+            }
+        }
+    }
+
+    // 每种问题最多保留100个
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    findings.retain(|item| {
+        let count = counts.entry(item.kind.clone()).or_default();
+        *count += 1;
+        *count <= MAX_FINDING_COUNT
+    });
+    findings
+}
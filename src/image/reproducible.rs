@@ -0,0 +1,203 @@
+use chrono::DateTime;
+use once_cell::sync::OnceCell;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use super::{ElfBinaryInfo, ImageHistory, ImageMergedFile};
+use crate::config::get_source_date_epoch;
+
+// 每种问题最多记录的文件数
+static MAX_FILE_COUNT: usize = 100;
+
+static EPOCH_FROM_CLI: &str = "cli";
+static EPOCH_FROM_CONFIG: &str = "config";
+static EPOCH_FROM_BUILD_ARG: &str = "build arg";
+static EPOCH_FROM_HISTORY: &str = "history";
+
+// layer中tar header的检测结果
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TarHeaderAudit {
+    pub layer_index: usize,
+    // 未按路径排序的entry数
+    pub unsorted_count: usize,
+    // header的格式：ustar、gnu、pax或v7
+    pub formats: Vec<String>,
+    // 带有atime或ctime的entry数
+    pub access_time_count: usize,
+    // 带有用户名或组名的entry数
+    pub owner_name_count: usize,
+    #[serde(skip)]
+    last_path: Option<String>,
+}
+
+impl TarHeaderAudit {
+    // 记录tar的entry，需按tar中的顺序调用
+    pub fn record(&mut self, path: &str, format: &str, access_time: bool, owner_name: bool) {
+        // 按路径的各部分比较（与目录遍历顺序一致）
+        let path = path.trim_start_matches("./").trim_end_matches('/');
+        if let Some(ref last_path) = self.last_path {
+            if compare_path(last_path, path) == Ordering::Greater {
+                self.unsorted_count += 1;
+            }
+        }
+        self.last_path = Some(path.to_string());
+        if !self.formats.iter().any(|item| item == format) {
+            self.formats.push(format.to_string());
+        }
+        if access_time {
+            self.access_time_count += 1;
+        }
+        if owner_name {
+            self.owner_name_count += 1;
+        }
+    }
+    // 是否存在影响可重现构建的问题
+    pub fn has_issue(&self) -> bool {
+        self.unsorted_count != 0
+            || self.formats.len() > 1
+            || self.access_time_count != 0
+            || self.owner_name_count != 0
+    }
+}
+
+fn compare_path(a: &str, b: &str) -> Ordering {
+    a.split('/').cmp(b.split('/'))
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReproducibleFile {
+    pub path: String,
+    pub layer_index: usize,
+    pub mtime: i64,
+    pub uid: u64,
+    pub gid: u64,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildIdInfo {
+    pub path: String,
+    pub layer_index: usize,
+    pub build_id: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReproducibilityReport {
+    // 用于对比文件修改时间的SOURCE_DATE_EPOCH
+    pub source_date_epoch: Option<i64>,
+    // SOURCE_DATE_EPOCH的来源：cli、config、build arg或history
+    pub source_date_epoch_from: String,
+    // 修改时间不等于SOURCE_DATE_EPOCH的文件（最多记录100个），来源为history时不检测
+    pub mtime_file_list: Vec<ReproducibleFile>,
+    pub mtime_file_count: usize,
+    // 属主未规范化的文件（最多记录100个）
+    pub owner_file_list: Vec<ReproducibleFile>,
+    pub owner_file_count: usize,
+    // 存在问题的layer的tar header
+    pub tar_header_list: Vec<TarHeaderAudit>,
+    // 嵌入了build id的二进制
+    pub build_id_list: Vec<BuildIdInfo>,
+}
+
+impl ReproducibilityReport {
+    pub fn has_issue(&self) -> bool {
+        self.mtime_file_count != 0 || self.owner_file_count != 0 || !self.tar_header_list.is_empty()
+    }
+}
+
+pub struct ReproducibilityOption<'a> {
+    pub history: &'a [ImageHistory],
+    // 最终文件系统
+    pub files: &'a [ImageMergedFile],
+    // /etc/passwd与/etc/group中的uid与gid
+    pub uids: &'a HashSet<u64>,
+    pub gids: &'a HashSet<u64>,
+    pub tar_headers: Vec<TarHeaderAudit>,
+    pub binaries: &'a [ElfBinaryInfo],
+    // 命令行指定的SOURCE_DATE_EPOCH
+    pub source_date_epoch: Option<i64>,
+}
+
+// 获取SOURCE_DATE_EPOCH：命令行参数、配置、构建参数或最后一条history的创建时间
+fn find_source_date_epoch(
+    history: &[ImageHistory],
+    source_date_epoch: Option<i64>,
+) -> Option<(i64, &'static str)> {
+    if let Some(value) = source_date_epoch {
+        return Some((value, EPOCH_FROM_CLI));
+    }
+    if let Some(value) = get_source_date_epoch() {
+        return Some((value, EPOCH_FROM_CONFIG));
+    }
+    static BUILD_ARG_REGEX: OnceCell<Regex> = OnceCell::new();
+    let regex = BUILD_ARG_REGEX.get_or_init(|| Regex::new(r"\bSOURCE_DATE_EPOCH=(\d+)").unwrap());
+    let build_arg = history
+        .iter()
+        .rev()
+        .filter_map(|item| item.created_by.as_ref())
+        .find_map(|created_by| regex.captures(created_by)?[1].parse::<i64>().ok());
+    if let Some(value) = build_arg {
+        return Some((value, EPOCH_FROM_BUILD_ARG));
+    }
+    let created = DateTime::parse_from_rfc3339(&history.last()?.created).ok()?;
+    Some((created.timestamp(), EPOCH_FROM_HISTORY))
+}
+
+fn new_file(file: &ImageMergedFile) -> ReproducibleFile {
+    ReproducibleFile {
+        path: format!("/{}", file.info.path),
+        layer_index: file.layer_index,
+        mtime: file.info.mtime,
+        uid: file.info.uid,
+        gid: file.info.gid,
+    }
+}
+
+// 检测影响可重现构建的文件修改时间、属主、tar header以及build id
+pub fn audit_reproducibility(opt: ReproducibilityOption) -> ReproducibilityReport {
+    let mut report = ReproducibilityReport::default();
+    if let Some((epoch, from)) = find_source_date_epoch(opt.history, opt.source_date_epoch) {
+        report.source_date_epoch = Some(epoch);
+        report.source_date_epoch_from = from.to_string();
+        // 从history推断的时间通常与文件的修改时间不一致，仅明确指定时才检测
+        let files = opt.files.iter().filter(|_| from != EPOCH_FROM_HISTORY);
+        for file in files.filter(|file| file.info.mtime != epoch) {
+            report.mtime_file_count += 1;
+            if report.mtime_file_list.len() < MAX_FILE_COUNT {
+                report.mtime_file_list.push(new_file(file));
+            }
+        }
+    }
+    // 属主为root或账号文件中存在的uid/gid视为已规范化
+    let is_normalized = |file: &ImageMergedFile| -> bool {
+        (file.info.uid == 0 || opt.uids.contains(&file.info.uid))
+            && (file.info.gid == 0 || opt.gids.contains(&file.info.gid))
+    };
+    for file in opt.files.iter().filter(|file| !is_normalized(file)) {
+        report.owner_file_count += 1;
+        if report.owner_file_list.len() < MAX_FILE_COUNT {
+            report.owner_file_list.push(new_file(file));
+        }
+    }
+    report.tar_header_list = opt
+        .tar_headers
+        .into_iter()
+        .filter(|item| item.has_issue())
+        .collect();
+    report.build_id_list = opt
+        .binaries
+        .iter()
+        .filter(|item| !item.build_id.is_empty())
+        .map(|item| BuildIdInfo {
+            path: item.path.clone(),
+            layer_index: item.layer_index,
+            build_id: item.build_id.clone(),
+        })
+        .collect();
+    report
+}
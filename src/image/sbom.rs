@@ -0,0 +1,411 @@
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use super::ImageMergedFile;
+
+static DPKG_STATUS: &str = "var/lib/dpkg/status";
+static DPKG_STATUS_DIR: &str = "var/lib/dpkg/status.d/";
+static APK_INSTALLED: &str = "lib/apk/db/installed";
+static OS_RELEASE_LIST: &[&str] = &["etc/os-release", "usr/lib/os-release"];
+
+// 包数据库文件大于此大小则不读取
+static MAX_PACKAGE_DB_SIZE: u64 = 32 * 1024 * 1024;
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// layer中包管理工具的数据库文件，读取layer时即解析，不保留文件内容
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackageDbFile {
+    pub path: String,
+    // os-release文件的系统信息
+    pub os: Option<OsReleaseInfo>,
+    // 包数据库中的包，未设置purl与layer
+    pub package_list: Vec<SbomPackage>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OsReleaseInfo {
+    pub id: String,
+    pub version_id: String,
+    pub pretty_name: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SbomPackage {
+    pub name: String,
+    // 源码包名称，如libssl3的源码包为openssl
+    pub source: String,
+    pub version: String,
+    pub arch: String,
+    // 包类型：deb或apk
+    pub ecosystem: String,
+    pub purl: String,
+    // 引入该版本的layer
+    pub layer_index: usize,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageSbom {
+    pub os: Option<OsReleaseInfo>,
+    pub package_list: Vec<SbomPackage>,
+}
+
+// 是否需要读取的包数据库文件
+pub fn is_package_db_file(path: &str, size: u64) -> bool {
+    if size == 0 || size > MAX_PACKAGE_DB_SIZE {
+        return false;
+    }
+    if path == DPKG_STATUS || path == APK_INSTALLED || OS_RELEASE_LIST.contains(&path) {
+        return true;
+    }
+    // distroless的每个包单独一个文件
+    path.starts_with(DPKG_STATUS_DIR) && !path.ends_with(".md5sums")
+}
+
+// 解析以空行分隔的记录，每行为key与value
+fn parse_stanzas(content: &str) -> Vec<HashMap<String, String>> {
+    let mut stanzas = vec![];
+    let mut current = HashMap::new();
+    for line in content.lines() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                stanzas.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        // 多行内容的后续行忽略
+        if line.starts_with(' ') || line.starts_with('\t') {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            current.insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+    if !current.is_empty() {
+        stanzas.push(current);
+    }
+    stanzas
+}
+
+fn parse_os_release(content: &str) -> OsReleaseInfo {
+    let mut info = OsReleaseInfo::default();
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value
+            .trim()
+            .trim_matches('"')
+            .trim_matches('\'')
+            .to_string();
+        match key.trim() {
+            "ID" => info.id = value,
+            "VERSION_ID" => info.version_id = value,
+            "PRETTY_NAME" => info.pretty_name = value,
+            _ => {}
+        }
+    }
+    info
+}
+
+// purl中的version需要转义
+fn encode_purl(value: &str) -> String {
+    let mut result = String::new();
+    for c in value.chars() {
+        if c.is_ascii_alphanumeric() || "-._~".contains(c) {
+            result.push(c);
+        } else {
+            let mut buf = [0; 4];
+            for b in c.encode_utf8(&mut buf).bytes() {
+                result += &format!("%{b:02X}");
+            }
+        }
+    }
+    result
+}
+
+// 解析包数据库文件，返回的package未设置purl与layer
+fn parse_packages(path: &str, content: &str) -> Vec<SbomPackage> {
+    let (ecosystem, keys) = if path == APK_INSTALLED {
+        ("apk", ["P", "V", "A", "o"])
+    } else {
+        ("deb", ["Package", "Version", "Architecture", "Source"])
+    };
+    let mut packages = vec![];
+    for stanza in parse_stanzas(content) {
+        // dpkg中已删除但仍保留配置的包忽略
+        if let Some(status) = stanza.get("Status") {
+            if !status.ends_with(" installed") {
+                continue;
+            }
+        }
+        let get = |key: &str| stanza.get(key).cloned().unwrap_or_default();
+        let name = get(keys[0]);
+        if name.is_empty() {
+            continue;
+        }
+        // dpkg的Source可能带有版本，如：openssl (3.0.11-1)，未指定则与包名相同
+        let source = get(keys[3])
+            .split_whitespace()
+            .next()
+            .map(|value| value.to_string())
+            .unwrap_or_else(|| name.clone());
+        packages.push(SbomPackage {
+            name,
+            source,
+            version: get(keys[1]),
+            arch: get(keys[2]),
+            ecosystem: ecosystem.to_string(),
+            ..Default::default()
+        });
+    }
+    packages
+}
+
+// 解析layer中的包数据库文件
+pub fn parse_package_db_file(path: &str, content: &[u8]) -> PackageDbFile {
+    let content = String::from_utf8_lossy(content);
+    if OS_RELEASE_LIST.contains(&path) {
+        return PackageDbFile {
+            path: path.to_string(),
+            os: Some(parse_os_release(&content)),
+            ..Default::default()
+        };
+    }
+    PackageDbFile {
+        path: path.to_string(),
+        package_list: parse_packages(path, &content),
+        ..Default::default()
+    }
+}
+
+fn new_purl(package: &SbomPackage, os: &Option<OsReleaseInfo>) -> String {
+    let default_namespace = if package.ecosystem == "apk" {
+        "alpine"
+    } else {
+        "debian"
+    };
+    let namespace = os
+        .as_ref()
+        .map(|item| item.id.clone())
+        .filter(|id| !id.is_empty())
+        .unwrap_or_else(|| default_namespace.to_string());
+    let mut purl = format!(
+        "pkg:{}/{namespace}/{}@{}",
+        package.ecosystem,
+        encode_purl(&package.name),
+        encode_purl(&package.version)
+    );
+    let mut qualifiers = vec![];
+    if !package.arch.is_empty() {
+        qualifiers.push(format!("arch={}", encode_purl(&package.arch)));
+    }
+    if let Some(os) = os {
+        if !os.version_id.is_empty() {
+            qualifiers.push(format!(
+                "distro={namespace}-{}",
+                encode_purl(&os.version_id)
+            ));
+        }
+    }
+    if !qualifiers.is_empty() {
+        purl += &format!("?{}", qualifiers.join("&"));
+    }
+    purl
+}
+
+// 根据最终文件系统的包数据库生成sbom
+// db_files为各layer中的包数据库文件（按layer顺序），用于判断包的引入layer
+pub fn build_sbom(db_files: &[(usize, PackageDbFile)], files: &[ImageMergedFile]) -> ImageSbom {
+    let find_db = |file: &ImageMergedFile| {
+        db_files
+            .iter()
+            .find(|(layer_index, db)| *layer_index == file.layer_index && db.path == file.info.path)
+            .map(|(_, db)| db)
+    };
+    let mut final_db_list = vec![];
+    let mut os_release_list = vec![];
+    for file in files.iter() {
+        let Some(db) = find_db(file) else {
+            continue;
+        };
+        if OS_RELEASE_LIST.contains(&db.path.as_str()) {
+            os_release_list.push(db);
+        } else {
+            final_db_list.push((file.layer_index, db));
+        }
+    }
+    // 优先使用/etc/os-release
+    let os = OS_RELEASE_LIST.iter().find_map(|path| {
+        os_release_list
+            .iter()
+            .find(|db| db.path == *path)
+            .and_then(|db| db.os.clone())
+    });
+
+    // 包的某个版本最早出现的layer
+    let mut introduced: HashMap<(String, String, String), usize> = HashMap::new();
+    for (layer_index, db) in db_files.iter() {
+        if OS_RELEASE_LIST.contains(&db.path.as_str()) {
+            continue;
+        }
+        for package in db.package_list.iter() {
+            introduced
+                .entry((
+                    package.ecosystem.clone(),
+                    package.name.clone(),
+                    package.version.clone(),
+                ))
+                .or_insert(*layer_index);
+        }
+    }
+
+    let mut package_list = vec![];
+    for (layer_index, db) in final_db_list {
+        for package in db.package_list.iter() {
+            let mut package = package.clone();
+            let key = (
+                package.ecosystem.clone(),
+                package.name.clone(),
+                package.version.clone(),
+            );
+            package.layer_index = introduced.get(&key).cloned().unwrap_or(layer_index);
+            package.purl = new_purl(&package, &os);
+            package_list.push(package);
+        }
+    }
+    package_list.sort_by(|a, b| a.name.cmp(&b.name).then(a.version.cmp(&b.version)));
+    package_list.dedup_by(|a, b| a.purl == b.purl);
+    ImageSbom { os, package_list }
+}
+
+fn now() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+// 生成CycloneDX格式的sbom
+fn to_cyclonedx(name: &str, sbom: &ImageSbom) -> Value {
+    let mut components = vec![];
+    if let Some(ref os) = sbom.os {
+        components.push(json!({
+            "type": "operating-system",
+            "name": os.id,
+            "version": os.version_id,
+            "description": os.pretty_name,
+        }));
+    }
+    for package in sbom.package_list.iter() {
+        components.push(json!({
+            "type": "library",
+            "bom-ref": package.purl,
+            "name": package.name,
+            "version": package.version,
+            "purl": package.purl,
+            "properties": [
+                {
+                    "name": "diving:layer",
+                    "value": (package.layer_index + 1).to_string(),
+                },
+                {
+                    "name": "diving:arch",
+                    "value": package.arch,
+                },
+            ],
+        }));
+    }
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "version": 1,
+        "metadata": {
+            "timestamp": now(),
+            "tools": {
+                "components": [
+                    {
+                        "type": "application",
+                        "name": "diving",
+                        "version": VERSION,
+                    }
+                ]
+            },
+            "component": {
+                "type": "container",
+                "name": name,
+            },
+        },
+        "components": components,
+    })
+}
+
+// 生成SPDX格式的sbom
+fn to_spdx(name: &str, sbom: &ImageSbom) -> Value {
+    let image_id = "SPDXRef-Image";
+    let mut packages = vec![json!({
+        "name": name,
+        "SPDXID": image_id,
+        "downloadLocation": "NOASSERTION",
+        "filesAnalyzed": false,
+        "primaryPackagePurpose": "CONTAINER",
+    })];
+    let mut relationships = vec![json!({
+        "spdxElementId": "SPDXRef-DOCUMENT",
+        "relationshipType": "DESCRIBES",
+        "relatedSpdxElement": image_id,
+    })];
+    for (index, package) in sbom.package_list.iter().enumerate() {
+        let id = format!("SPDXRef-Package-{}", index + 1);
+        packages.push(json!({
+            "name": package.name,
+            "SPDXID": id,
+            "versionInfo": package.version,
+            "downloadLocation": "NOASSERTION",
+            "filesAnalyzed": false,
+            "licenseConcluded": "NOASSERTION",
+            "licenseDeclared": "NOASSERTION",
+            "comment": format!("arch: {}, layer: {}", package.arch, package.layer_index + 1),
+            "externalRefs": [
+                {
+                    "referenceCategory": "PACKAGE-MANAGER",
+                    "referenceType": "purl",
+                    "referenceLocator": package.purl,
+                }
+            ],
+        }));
+        relationships.push(json!({
+            "spdxElementId": image_id,
+            "relationshipType": "CONTAINS",
+            "relatedSpdxElement": id,
+        }));
+    }
+    json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "name": name,
+        "documentNamespace": format!(
+            "https://github.com/vicanso/diving-rs/spdx/{}-{}",
+            encode_purl(name),
+            nanoid::nanoid!()
+        ),
+        "creationInfo": {
+            "created": now(),
+            "creators": [format!("Tool: diving-{VERSION}")],
+        },
+        "packages": packages,
+        "relationships": relationships,
+    })
+}
+
+// 生成指定格式的sbom，格式不支持则返回空
+pub fn to_sbom_document(format: &str, name: &str, sbom: &ImageSbom) -> Option<Value> {
+    match format {
+        "cyclonedx" => Some(to_cyclonedx(name, sbom)),
+        "spdx" => Some(to_spdx(name, sbom)),
+        _ => None,
+    }
+}
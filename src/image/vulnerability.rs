@@ -0,0 +1,694 @@
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use tracing::{info, warn};
+
+use super::{ImageSbom, LanguageComponent};
+use crate::config::get_osv_path;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VulnerabilityFinding {
+    // osv的id，如CVE-2023-38545、GHSA-xxxx
+    pub id: String,
+    pub aliases: Vec<String>,
+    pub summary: String,
+    // 严重级别（osv数据库中提供时）
+    pub severity: String,
+    pub ecosystem: String,
+    pub package: String,
+    pub version: String,
+    // 修复的版本
+    pub fixed_version: String,
+    // 引入该包的layer
+    pub layer_index: usize,
+}
+
+#[derive(Default, Debug, Clone, Deserialize)]
+struct OsvEvent {
+    introduced: Option<String>,
+    fixed: Option<String>,
+    last_affected: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, Deserialize)]
+struct OsvRange {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    events: Vec<OsvEvent>,
+}
+
+#[derive(Default, Debug, Clone, Deserialize)]
+struct OsvPackage {
+    ecosystem: String,
+    name: String,
+}
+
+#[derive(Default, Debug, Clone, Deserialize)]
+struct OsvAffected {
+    package: Option<OsvPackage>,
+    #[serde(default)]
+    ranges: Vec<OsvRange>,
+    #[serde(default)]
+    versions: Vec<String>,
+    database_specific: Option<serde_json::Value>,
+}
+
+#[derive(Default, Debug, Clone, Deserialize)]
+struct OsvVulnerability {
+    id: String,
+    #[serde(default)]
+    aliases: Vec<String>,
+    #[serde(default)]
+    summary: String,
+    #[serde(default)]
+    affected: Vec<OsvAffected>,
+    database_specific: Option<serde_json::Value>,
+}
+
+struct OsvEntry {
+    vulnerability: usize,
+    // 如Debian:12中的12，未指定则匹配所有版本
+    release: String,
+    affected: OsvAffected,
+}
+
+pub struct OsvDatabase {
+    vulnerabilities: Vec<OsvVulnerability>,
+    // 以生态与包名索引
+    entries: HashMap<(String, String), Vec<OsvEntry>>,
+}
+
+// 支持匹配的生态
+static SUPPORTED_ECOSYSTEMS: &[&str] = &["Debian", "Ubuntu", "Alpine", "npm", "PyPI"];
+
+// 规范化系统版本，alpine仅使用主次版本，如3.18.4为v3.18
+fn normalize_release(ecosystem: &str, release: &str) -> String {
+    if ecosystem != "Alpine" || release.is_empty() {
+        return release.to_string();
+    }
+    let minor: Vec<&str> = release.trim_start_matches('v').split('.').take(2).collect();
+    format!("v{}", minor.join("."))
+}
+
+// pypi的包名不区分大小写，且-_.等同
+fn normalize_name(ecosystem: &str, name: &str) -> String {
+    if ecosystem == "PyPI" {
+        return name.to_lowercase().replace(['_', '.'], "-");
+    }
+    name.to_string()
+}
+
+impl OsvDatabase {
+    fn add(&mut self, vulnerability: OsvVulnerability) {
+        let index = self.vulnerabilities.len();
+        let mut added = false;
+        for affected in vulnerability.affected.iter() {
+            let Some(ref package) = affected.package else {
+                continue;
+            };
+            let (ecosystem, release) = package
+                .ecosystem
+                .split_once(':')
+                .unwrap_or((&package.ecosystem, ""));
+            // 如Ubuntu:22.04:LTS、Ubuntu:Pro:18.04:LTS仅使用版本号
+            let release = release
+                .split(':')
+                .find(|item| item.starts_with(|c: char| c.is_ascii_digit()))
+                .or_else(|| release.split(':').next())
+                .unwrap_or_default();
+            if !SUPPORTED_ECOSYSTEMS.contains(&ecosystem) {
+                continue;
+            }
+            added = true;
+            self.entries
+                .entry((
+                    ecosystem.to_string(),
+                    normalize_name(ecosystem, &package.name),
+                ))
+                .or_default()
+                .push(OsvEntry {
+                    vulnerability: index,
+                    release: normalize_release(ecosystem, release),
+                    affected: affected.clone(),
+                });
+        }
+        if added {
+            self.vulnerabilities.push(vulnerability);
+        }
+    }
+    fn add_json(&mut self, data: &[u8]) {
+        // 支持单个或数组形式
+        if let Ok(vulnerability) = serde_json::from_slice::<OsvVulnerability>(data) {
+            self.add(vulnerability);
+        } else if let Ok(list) = serde_json::from_slice::<Vec<OsvVulnerability>>(data) {
+            for vulnerability in list {
+                self.add(vulnerability);
+            }
+        }
+    }
+    fn add_zip(&mut self, data: &[u8]) {
+        let Ok(mut archive) = zip::ZipArchive::new(std::io::Cursor::new(data)) else {
+            return;
+        };
+        for index in 0..archive.len() {
+            let Ok(mut file) = archive.by_index(index) else {
+                continue;
+            };
+            if !file.name().ends_with(".json") {
+                continue;
+            }
+            let mut buf = vec![];
+            if file.read_to_end(&mut buf).is_ok() {
+                self.add_json(&buf);
+            }
+        }
+    }
+    fn load_dir(&mut self, dir: &Path) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            warn!(
+                path = dir.to_string_lossy().to_string(),
+                "read osv dir fail"
+            );
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                self.load_dir(&path);
+                continue;
+            }
+            let name = path.to_string_lossy().to_string();
+            let is_json = name.ends_with(".json");
+            if !is_json && !name.ends_with(".zip") {
+                continue;
+            }
+            let Ok(data) = fs::read(&path) else {
+                continue;
+            };
+            if is_json {
+                self.add_json(&data);
+            } else {
+                self.add_zip(&data);
+            }
+        }
+    }
+    fn find(
+        &self,
+        ecosystem: &str,
+        release: &str,
+        name: &str,
+        version: &str,
+    ) -> Vec<(&OsvVulnerability, &OsvAffected)> {
+        let key = (ecosystem.to_string(), normalize_name(ecosystem, name));
+        let Some(entries) = self.entries.get(&key) else {
+            return vec![];
+        };
+        entries
+            .iter()
+            .filter(|entry| entry.release.is_empty() || entry.release == release)
+            .filter(|entry| is_affected(ecosystem, &entry.affected, version))
+            .map(|entry| (&self.vulnerabilities[entry.vulnerability], &entry.affected))
+            .collect()
+    }
+}
+
+// dpkg的版本中非数字部分的比较，~小于任何字符（包括空）
+fn dpkg_order(c: Option<char>) -> i32 {
+    match c {
+        None => 0,
+        Some('~') => -1,
+        Some(c) if c.is_ascii_digit() => 0,
+        Some(c) if c.is_ascii_alphabetic() => c as i32,
+        Some(c) => c as i32 + 256,
+    }
+}
+
+fn dpkg_compare_part(a: &str, b: &str) -> Ordering {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        // 先比较非数字部分
+        while (i < a.len() && !a[i].is_ascii_digit()) || (j < b.len() && !b[j].is_ascii_digit()) {
+            let ac = dpkg_order(a.get(i).cloned().filter(|c| !c.is_ascii_digit()));
+            let bc = dpkg_order(b.get(j).cloned().filter(|c| !c.is_ascii_digit()));
+            if ac != bc {
+                return ac.cmp(&bc);
+            }
+            if i < a.len() && !a[i].is_ascii_digit() {
+                i += 1;
+            }
+            if j < b.len() && !b[j].is_ascii_digit() {
+                j += 1;
+            }
+        }
+        // 再比较数字部分
+        let start = i;
+        while i < a.len() && a[i].is_ascii_digit() {
+            i += 1;
+        }
+        let an: u128 = a[start..i].iter().collect::<String>().parse().unwrap_or(0);
+        let start = j;
+        while j < b.len() && b[j].is_ascii_digit() {
+            j += 1;
+        }
+        let bn: u128 = b[start..j].iter().collect::<String>().parse().unwrap_or(0);
+        if an != bn {
+            return an.cmp(&bn);
+        }
+    }
+    Ordering::Equal
+}
+
+// 按dpkg的规则比较版本：epoch:upstream-revision
+fn dpkg_compare(a: &str, b: &str) -> Ordering {
+    let split = |value: &str| {
+        let (epoch, rest) = value.split_once(':').unwrap_or(("0", value));
+        let (upstream, revision) = rest.rsplit_once('-').unwrap_or((rest, ""));
+        (
+            epoch.parse::<u64>().unwrap_or(0),
+            upstream.to_string(),
+            revision.to_string(),
+        )
+    };
+    let (a_epoch, a_upstream, a_revision) = split(a);
+    let (b_epoch, b_upstream, b_revision) = split(b);
+    a_epoch
+        .cmp(&b_epoch)
+        .then_with(|| dpkg_compare_part(&a_upstream, &b_upstream))
+        .then_with(|| dpkg_compare_part(&a_revision, &b_revision))
+}
+
+// 预发布版本的标识，排序小于正式版本
+fn pre_release_rank(value: &str) -> Option<i32> {
+    match value {
+        "dev" => Some(0),
+        "a" | "alpha" => Some(1),
+        "b" | "beta" => Some(2),
+        "c" | "pre" | "preview" | "rc" => Some(3),
+        _ => None,
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum VersionToken {
+    Number(u128),
+    Text(String),
+}
+
+fn tokenize(value: &str) -> Vec<VersionToken> {
+    let mut tokens = vec![];
+    let mut current = String::new();
+    let mut is_digit = false;
+    let value = value.trim_start_matches(['v', 'V']);
+    // semver的build信息不参与比较
+    let value = value.split('+').next().unwrap_or_default();
+    for c in value.chars() {
+        if !c.is_ascii_alphanumeric() {
+            if !current.is_empty() {
+                tokens.push(new_token(&current, is_digit));
+                current.clear();
+            }
+            continue;
+        }
+        if !current.is_empty() && c.is_ascii_digit() != is_digit {
+            tokens.push(new_token(&current, is_digit));
+            current.clear();
+        }
+        is_digit = c.is_ascii_digit();
+        current.push(c.to_ascii_lowercase());
+    }
+    if !current.is_empty() {
+        tokens.push(new_token(&current, is_digit));
+    }
+    tokens
+}
+
+fn new_token(value: &str, is_digit: bool) -> VersionToken {
+    if is_digit {
+        VersionToken::Number(value.parse().unwrap_or(0))
+    } else {
+        VersionToken::Text(value.to_string())
+    }
+}
+
+// 通用的版本比较（semver、pep440、apk），数字按数值比较，预发布标识小于正式版本
+fn generic_compare(a: &str, b: &str) -> Ordering {
+    let a = tokenize(a);
+    let b = tokenize(b);
+    let count = a.len().max(b.len());
+    for index in 0..count {
+        let result = match (a.get(index), b.get(index)) {
+            (Some(VersionToken::Number(x)), Some(VersionToken::Number(y))) => x.cmp(y),
+            (Some(VersionToken::Text(x)), Some(VersionToken::Text(y))) => {
+                match (pre_release_rank(x), pre_release_rank(y)) {
+                    (Some(x), Some(y)) => x.cmp(&y),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    _ => x.cmp(y),
+                }
+            }
+            // 数字大于预发布标识，小于其它标识（如post、r）
+            (Some(VersionToken::Number(_)), Some(VersionToken::Text(y))) => {
+                if pre_release_rank(y).is_some() {
+                    Ordering::Greater
+                } else {
+                    Ordering::Less
+                }
+            }
+            (Some(VersionToken::Text(x)), Some(VersionToken::Number(_))) => {
+                if pre_release_rank(x).is_some() {
+                    Ordering::Less
+                } else {
+                    Ordering::Greater
+                }
+            }
+            (Some(VersionToken::Text(x)), None) => {
+                if pre_release_rank(x).is_some() {
+                    Ordering::Less
+                } else {
+                    Ordering::Greater
+                }
+            }
+            (None, Some(VersionToken::Text(y))) => {
+                if pre_release_rank(y).is_some() {
+                    Ordering::Greater
+                } else {
+                    Ordering::Less
+                }
+            }
+            (Some(VersionToken::Number(x)), None) => x.cmp(&0),
+            (None, Some(VersionToken::Number(y))) => 0.cmp(y),
+            (None, None) => Ordering::Equal,
+        };
+        if result != Ordering::Equal {
+            return result;
+        }
+    }
+    Ordering::Equal
+}
+
+fn compare_version(ecosystem: &str, a: &str, b: &str) -> Ordering {
+    // 起始版本0小于所有版本
+    if a == "0" || b == "0" {
+        return match (a == "0", b == "0") {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Less,
+            _ => Ordering::Greater,
+        };
+    }
+    match ecosystem {
+        "Debian" | "Ubuntu" => dpkg_compare(a, b),
+        _ => generic_compare(a, b),
+    }
+}
+
+// 根据osv的版本范围判断是否受影响
+fn is_affected(ecosystem: &str, affected: &OsvAffected, version: &str) -> bool {
+    if affected.versions.iter().any(|item| item == version) {
+        return true;
+    }
+    for range in affected.ranges.iter() {
+        if range.kind != "ECOSYSTEM" && range.kind != "SEMVER" {
+            continue;
+        }
+        let mut events: Vec<(&str, &str)> = range
+            .events
+            .iter()
+            .filter_map(|event| {
+                if let Some(ref value) = event.introduced {
+                    Some(("introduced", value.as_str()))
+                } else if let Some(ref value) = event.fixed {
+                    Some(("fixed", value.as_str()))
+                } else {
+                    event
+                        .last_affected
+                        .as_ref()
+                        .map(|value| ("last_affected", value.as_str()))
+                }
+            })
+            .collect();
+        events.sort_by(|a, b| compare_version(ecosystem, a.1, b.1));
+        let mut vulnerable = false;
+        for (kind, value) in events {
+            let result = compare_version(ecosystem, version, value);
+            match kind {
+                "introduced" if result != Ordering::Less => vulnerable = true,
+                "fixed" if result != Ordering::Less => vulnerable = false,
+                "last_affected" if result == Ordering::Greater => vulnerable = false,
+                _ => {}
+            }
+        }
+        if vulnerable {
+            return true;
+        }
+    }
+    false
+}
+
+// 首个修复的版本
+fn get_fixed_version(affected: &OsvAffected) -> String {
+    affected
+        .ranges
+        .iter()
+        .flat_map(|range| range.events.iter())
+        .find_map(|event| event.fixed.clone())
+        .unwrap_or_default()
+}
+
+fn get_severity(vulnerability: &OsvVulnerability, affected: &OsvAffected) -> String {
+    [
+        &affected.database_specific,
+        &vulnerability.database_specific,
+    ]
+    .iter()
+    .filter_map(|item| item.as_ref())
+    .find_map(|item| {
+        item.get("severity")?
+            .as_str()
+            .map(|value| value.to_uppercase())
+    })
+    .unwrap_or_default()
+}
+
+// 获取osv数据库，未配置则为空
+pub fn get_osv_database() -> Option<&'static OsvDatabase> {
+    static OSV_DATABASE: OnceCell<Option<OsvDatabase>> = OnceCell::new();
+    OSV_DATABASE
+        .get_or_init(|| {
+            let path = get_osv_path()?;
+            let mut db = OsvDatabase {
+                vulnerabilities: vec![],
+                entries: HashMap::new(),
+            };
+            db.load_dir(&path);
+            info!(
+                path = path.to_string_lossy().to_string(),
+                count = db.vulnerabilities.len(),
+                "load osv database done"
+            );
+            Some(db)
+        })
+        .as_ref()
+}
+
+// 匹配系统包与语言组件的漏洞
+pub fn find_vulnerabilities(
+    db: &OsvDatabase,
+    sbom: &ImageSbom,
+    components: &[LanguageComponent],
+) -> Vec<VulnerabilityFinding> {
+    let os_id = sbom
+        .os
+        .as_ref()
+        .map(|item| item.id.as_str())
+        .unwrap_or_default();
+    // 未知的系统不匹配系统包，避免误报
+    let os_ecosystem = match os_id {
+        "debian" => Some(("Debian", "deb")),
+        "ubuntu" => Some(("Ubuntu", "deb")),
+        "alpine" => Some(("Alpine", "apk")),
+        _ => None,
+    };
+    let mut packages = vec![];
+    if let Some((ecosystem, package_ecosystem)) = os_ecosystem {
+        // alpine的osv生态为Alpine:v3.18
+        let release = normalize_release(
+            ecosystem,
+            &sbom
+                .os
+                .as_ref()
+                .map(|item| item.version_id.clone())
+                .unwrap_or_default(),
+        );
+        for package in sbom.package_list.iter() {
+            if package.ecosystem != package_ecosystem {
+                continue;
+            }
+            // 系统包的漏洞以源码包记录
+            packages.push((
+                ecosystem,
+                release.clone(),
+                &package.source,
+                &package.name,
+                &package.version,
+                package.layer_index,
+            ));
+        }
+    }
+    for component in components.iter() {
+        let ecosystem = match component.ecosystem.as_str() {
+            "npm" => "npm",
+            "pypi" => "PyPI",
+            _ => continue,
+        };
+        packages.push((
+            ecosystem,
+            "".to_string(),
+            &component.name,
+            &component.name,
+            &component.version,
+            component.layer_index,
+        ));
+    }
+
+    let mut findings = vec![];
+    for (ecosystem, release, source, name, version, layer_index) in packages {
+        for (vulnerability, affected) in db.find(ecosystem, &release, source, version) {
+            findings.push(VulnerabilityFinding {
+                id: vulnerability.id.clone(),
+                aliases: vulnerability.aliases.clone(),
+                summary: vulnerability.summary.clone(),
+                severity: get_severity(vulnerability, affected),
+                ecosystem: ecosystem.to_string(),
+                package: name.clone(),
+                version: version.clone(),
+                fixed_version: get_fixed_version(affected),
+                layer_index,
+            });
+        }
+    }
+    findings.sort_by(|a, b| {
+        a.layer_index
+            .cmp(&b.layer_index)
+            .then_with(|| a.package.cmp(&b.package))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    findings.dedup_by(|a, b| a.id == b.id && a.package == b.package && a.version == b.version);
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        dpkg_compare, generic_compare, is_affected, normalize_release, OsvAffected, OsvEvent,
+        OsvRange,
+    };
+    use std::cmp::Ordering;
+
+    fn new_affected(events: Vec<OsvEvent>) -> OsvAffected {
+        OsvAffected {
+            ranges: vec![OsvRange {
+                kind: "ECOSYSTEM".to_string(),
+                events,
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn introduced(value: &str) -> OsvEvent {
+        OsvEvent {
+            introduced: Some(value.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_dpkg_compare() {
+        assert_eq!(Ordering::Less, dpkg_compare("1.0~rc1", "1.0"));
+        assert_eq!(Ordering::Less, dpkg_compare("1.0~rc1", "1.0~rc2"));
+        assert_eq!(Ordering::Greater, dpkg_compare("1:0.9", "2.0"));
+        assert_eq!(Ordering::Equal, dpkg_compare("0:1.0-1", "1.0-1"));
+        assert_eq!(Ordering::Less, dpkg_compare("1.0-1", "1.0-2"));
+        assert_eq!(
+            Ordering::Less,
+            dpkg_compare("2.36-9+deb12u4", "2.36-9+deb12u7")
+        );
+        assert_eq!(Ordering::Greater, dpkg_compare("1.0a", "1.0"));
+    }
+
+    #[test]
+    fn test_generic_compare() {
+        // apk的-r后缀
+        assert_eq!(Ordering::Less, generic_compare("1.2.3-r1", "1.2.3-r2"));
+        assert_eq!(Ordering::Less, generic_compare("1.2.3-r9", "1.2.3-r10"));
+        assert_eq!(Ordering::Less, generic_compare("1.2.3", "1.2.3-r0"));
+        assert_eq!(Ordering::Less, generic_compare("1.2.3-r5", "1.2.4-r0"));
+        // pep440的预发布与post版本
+        assert_eq!(Ordering::Less, generic_compare("1.0rc1", "1.0"));
+        assert_eq!(Ordering::Less, generic_compare("1.0a1", "1.0b1"));
+        assert_eq!(Ordering::Less, generic_compare("1.0.dev1", "1.0a1"));
+        assert_eq!(Ordering::Greater, generic_compare("1.0.post1", "1.0"));
+        assert_eq!(Ordering::Less, generic_compare("1.0", "1.0.1"));
+        // semver
+        assert_eq!(Ordering::Less, generic_compare("1.0.0-beta.2", "1.0.0"));
+        assert_eq!(Ordering::Equal, generic_compare("v1.0.0+build1", "1.0.0"));
+    }
+
+    #[test]
+    fn test_is_affected() {
+        let affected = new_affected(vec![
+            introduced("0"),
+            OsvEvent {
+                fixed: Some("1.2.0".to_string()),
+                ..Default::default()
+            },
+        ]);
+        assert!(is_affected("npm", &affected, "1.1.9"));
+        assert!(!is_affected("npm", &affected, "1.2.0"));
+        assert!(!is_affected("npm", &affected, "1.3.0"));
+
+        let affected = new_affected(vec![
+            introduced("1.0"),
+            OsvEvent {
+                last_affected: Some("1.2".to_string()),
+                ..Default::default()
+            },
+        ]);
+        assert!(!is_affected("PyPI", &affected, "0.9"));
+        assert!(is_affected("PyPI", &affected, "1.0"));
+        assert!(is_affected("PyPI", &affected, "1.2"));
+        assert!(!is_affected("PyPI", &affected, "1.2.1"));
+
+        let affected = new_affected(vec![
+            introduced("0"),
+            OsvEvent {
+                fixed: Some("3.0.11-1~deb12u2".to_string()),
+                ..Default::default()
+            },
+        ]);
+        assert!(is_affected("Debian", &affected, "3.0.11-1~deb12u1"));
+        assert!(!is_affected("Debian", &affected, "3.0.11-1~deb12u2"));
+        assert!(!is_affected("Debian", &affected, "3.0.11-1"));
+
+        let affected = OsvAffected {
+            versions: vec!["2.0.0".to_string()],
+            ..Default::default()
+        };
+        assert!(is_affected("npm", &affected, "2.0.0"));
+        assert!(!is_affected("npm", &affected, "2.0.1"));
+    }
+
+    #[test]
+    fn test_normalize_release() {
+        assert_eq!("v3.18", normalize_release("Alpine", "3.18.4"));
+        assert_eq!("v3.18", normalize_release("Alpine", "v3.18"));
+        assert_eq!("12", normalize_release("Debian", "12"));
+    }
+}
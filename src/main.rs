@@ -25,7 +25,7 @@ mod ui;
 mod util;
 
 use controller::new_router;
-use image::{analyze_docker_image, parse_image_info};
+use image::{analyze_docker_image, diff_image, parse_image_info, to_sbom_document, Op};
 use middleware::{access_log, entry};
 use store::clear_blob_files;
 use task_local::{generate_trace_id, TRACE_ID};
@@ -45,6 +45,26 @@ struct Args {
     /// The result output file
     #[arg(short, long)]
     output_file: Option<String>,
+    /// Compare the image with another image, e.g. `diving app:1.4 --diff app:1.5`
+    #[arg(short, long)]
+    diff: Option<String>,
+    /// The base image, its layers are matched to find the base/application boundary,
+    /// default is the `org.opencontainers.image.base.name` annotation
+    #[arg(short, long)]
+    base: Option<String>,
+    /// Generate the SBOM of the image from the os package databases,
+    /// `cyclonedx` or `spdx`, it is written to the output file or stdout
+    #[arg(long)]
+    sbom: Option<String>,
+    /// Compare two builds of the same commit with `--diff`, list the entries that differ
+    /// in metadata only, it fails if the builds are not identical.
+    /// Without `--diff`, list each file of the reproducible check instead of the counts
+    #[arg(long)]
+    reproducible: bool,
+    /// The SOURCE_DATE_EPOCH that the mtime of files should be equal to,
+    /// default is the `source_date_epoch` config or the build arg in the history
+    #[arg(long)]
+    source_date_epoch: Option<i64>,
 }
 
 impl Args {
@@ -71,6 +91,8 @@ fn init_logger() {
         .with_max_level(level)
         .with_timer(timer)
         .with_ansi(env != "production")
+        // 日志输出至stderr，避免与输出至stdout的结果（如sbom）混在一起
+        .with_writer(std::io::stderr)
         .finish();
     tracing::subscriber::set_global_default(subscriber).expect("setting default subscriber failed");
 }
@@ -102,10 +124,18 @@ fn is_ci() -> bool {
 }
 
 // 分析镜像（错误直接以字符串返回）
-async fn analyze(image: String, output_file: String) -> Result<(), String> {
+async fn analyze(
+    image: String,
+    base: String,
+    output_file: String,
+    reproducible: bool,
+    source_date_epoch: Option<i64>,
+) -> Result<(), String> {
     // 命令行模式下清除过期数据
     clear_blob_files().await.map_err(|item| item.to_string())?;
-    let image_info = parse_image_info(&image);
+    let mut image_info = parse_image_info(&image);
+    image_info.base = base;
+    image_info.source_date_epoch = source_date_epoch;
     let result = analyze_docker_image(image_info)
         .await
         .map_err(|item| item.to_string())?;
@@ -121,8 +151,238 @@ async fn analyze(image: String, output_file: String) -> Result<(), String> {
             summary.wasted_size,
             ByteSize(summary.wasted_size)
         );
+        println!(
+            "  duplicate bytes: {} bytes ({})",
+            summary.duplicate_size,
+            ByteSize(summary.duplicate_size)
+        );
+        println!(
+            "  added then removed bytes: {} bytes ({})",
+            summary.ghost_size,
+            ByteSize(summary.ghost_size)
+        );
+
+        println!(
+            "  package cache bytes: {} bytes ({})",
+            summary.package_cache_size,
+            ByteSize(summary.package_cache_size)
+        );
+        for cache in summary.package_cache_list.iter() {
+            let layers: Vec<String> = cache
+                .layers
+                .iter()
+                .map(|index| (index + 1).to_string())
+                .collect();
+            println!(
+                "    {}: {} (layers: {})",
+                cache.ecosystem,
+                ByteSize(cache.size),
+                layers.join(", ")
+            );
+        }
+        println!(
+            "  estimated strip savings: {} bytes ({})",
+            summary.strip_savings,
+            ByteSize(summary.strip_savings)
+        );
+        for binary in result
+            .elf_binary_list
+            .iter()
+            .filter(|item| item.strip_savings() > 0)
+        {
+            println!(
+                "    {}: {} (layer {}, {})",
+                binary.path,
+                ByteSize(binary.strip_savings()),
+                binary.layer_index + 1,
+                binary.linkage
+            );
+        }
+        // 各layer的压缩率以及以zstd压缩后预计的大小
+        println!("  layer compression:");
+        for (index, layer) in result.layers.iter().enumerate() {
+            let Some(ref compression) = layer.compression else {
+                continue;
+            };
+            let mut message = format!(
+                "    layer {}: {} / {} ({:.1} %)",
+                index + 1,
+                ByteSize(layer.size),
+                ByteSize(layer.unpack_size),
+                compression.ratio * 100.0
+            );
+            let mut flags = vec![];
+            if compression.uncompressed {
+                flags.push("uncompressed");
+            }
+            if compression.precompressed {
+                flags.push("precompressed");
+            }
+            if !flags.is_empty() {
+                message += &format!(" [{}]", flags.join(", "));
+            }
+            for estimate in compression.zstd_estimate_list.iter() {
+                message += &format!(", zstd -{}: {}", estimate.level, ByteSize(estimate.size));
+            }
+            println!("{message}");
+        }
+        for item in summary.zstd_savings_list.iter() {
+            let savings = ByteSize(item.savings.unsigned_abs());
+            println!(
+                "  estimated zstd -{} savings: {}{} ({} -> {})",
+                item.level,
+                if item.savings < 0 { "-" } else { "" },
+                savings,
+                ByteSize(item.size),
+                ByteSize(item.zstd_size)
+            );
+        }
+        if !result.vulnerability_list.is_empty() {
+            println!("  vulnerabilities: {}", summary.vulnerability_count);
+            for item in summary.vulnerability_list.iter() {
+                println!("    layer {}: {}", item.layer_index + 1, item.count);
+            }
+            for finding in result.vulnerability_list.iter() {
+                let mut message = format!(
+                    "    {} {}@{} (layer {})",
+                    finding.id,
+                    finding.package,
+                    finding.version,
+                    finding.layer_index + 1
+                );
+                if !finding.severity.is_empty() {
+                    message += &format!(" {}", finding.severity);
+                }
+                if !finding.fixed_version.is_empty() {
+                    message += &format!(", fixed in {}", finding.fixed_version);
+                }
+                println!("{message}");
+            }
+        }
+        if !result.reproducibility.build_id_list.is_empty() {
+            println!(
+                "  embedded build ids: {}",
+                result.reproducibility.build_id_list.len()
+            );
+            for item in result.reproducibility.build_id_list.iter() {
+                println!(
+                    "    {}: {} (layer {})",
+                    item.path,
+                    item.build_id,
+                    item.layer_index + 1
+                );
+            }
+        }
+        if let Some(ref base_image) = result.base_image {
+            println!(
+                "  base image: {} ({} layers, {} bytes)",
+                base_image.name, base_image.layer_count, base_image.total_size
+            );
+            println!("  application bytes: {} bytes", base_image.app_total_size);
+        }
+
+        for warning in result.warning_list.iter() {
+            println!("{}: {warning}", "WARN".yellow());
+        }
+        // 权限与属主的问题
+        for finding in result.permission_finding_list.iter() {
+            println!(
+                "{}: permission check, {}: {} ({} {}:{}, layer {})",
+                "WARN".yellow(),
+                finding.kind,
+                finding.path,
+                finding.mode,
+                finding.uid,
+                finding.gid,
+                finding.layer_index + 1
+            );
+        }
+
+        // 影响可重现构建的文件与tar header
+        let reproducibility = &result.reproducibility;
+        if reproducible {
+            for file in reproducibility.mtime_file_list.iter() {
+                println!(
+                    "{}: reproducible check, mtime {} is not equal to SOURCE_DATE_EPOCH {} ({}): {} (layer {})",
+                    "WARN".yellow(),
+                    file.mtime,
+                    reproducibility.source_date_epoch.unwrap_or_default(),
+                    reproducibility.source_date_epoch_from,
+                    file.path,
+                    file.layer_index + 1
+                );
+            }
+            for file in reproducibility.owner_file_list.iter() {
+                println!(
+                    "{}: reproducible check, owner {}:{} is not normalized: {} (layer {})",
+                    "WARN".yellow(),
+                    file.uid,
+                    file.gid,
+                    file.path,
+                    file.layer_index + 1
+                );
+            }
+        } else {
+            // 未指定--reproducible时仅输出文件数
+            if reproducibility.mtime_file_count != 0 {
+                println!(
+                    "{}: reproducible check, {} files mtime is not equal to SOURCE_DATE_EPOCH {} ({}), use --reproducible to list them",
+                    "WARN".yellow(),
+                    reproducibility.mtime_file_count,
+                    reproducibility.source_date_epoch.unwrap_or_default(),
+                    reproducibility.source_date_epoch_from
+                );
+            }
+            if reproducibility.owner_file_count != 0 {
+                println!(
+                    "{}: reproducible check, {} files owner is not normalized, use --reproducible to list them",
+                    "WARN".yellow(),
+                    reproducibility.owner_file_count
+                );
+            }
+        }
+        for item in reproducibility.tar_header_list.iter() {
+            println!(
+                "{}: reproducible check, layer {} tar headers: {} unsorted, formats {}, {} with atime/ctime, {} with user/group name",
+                "WARN".yellow(),
+                item.layer_index + 1,
+                item.unsorted_count,
+                item.formats.join("/"),
+                item.access_time_count,
+                item.owner_name_count
+            );
+        }
 
         let mut passed = true;
+        // 自定义的检测规则
+        for issue in result.lint_issue_list.iter() {
+            let level = if issue.is_error() {
+                passed = false;
+                "FAIL".red()
+            } else {
+                "WARN".yellow()
+            };
+            let mut message = format!("{level}: {} check, {}", issue.rule, issue.message);
+            if !issue.paths.is_empty() {
+                message += &format!(", {} files: {}", issue.count, issue.paths.join(", "));
+            }
+            println!("{message}");
+        }
+        // 检测到的敏感信息
+        for finding in result.secret_list.iter() {
+            let layer = finding
+                .layer_index
+                .map(|index| format!("layer {}, ", index + 1))
+                .unwrap_or_default();
+            println!(
+                "{}: secret check, {layer}{}, {}: {}",
+                "FAIL".red(),
+                finding.path,
+                finding.rule,
+                finding.redacted
+            );
+            passed = false;
+        }
         if summary.score < lowest_efficiency {
             println!(
                 "{}: lowest efficiency check, lowest: {}",
@@ -131,6 +391,26 @@ async fn analyze(image: String, output_file: String) -> Result<(), String> {
             );
             passed = false;
         }
+        if let Some(highest_package_cache_bytes) = config::get_highest_package_cache_bytes() {
+            if summary.package_cache_size > highest_package_cache_bytes {
+                println!(
+                    "{}: highest package cache bytes check, highest: {}",
+                    "FAIL".red(),
+                    ByteSize(highest_package_cache_bytes)
+                );
+                passed = false;
+            }
+        }
+        if let Some(highest_vulnerability_count) = config::get_highest_vulnerability_count() {
+            if summary.vulnerability_count > highest_vulnerability_count {
+                println!(
+                    "{}: highest vulnerability count check, highest: {}",
+                    "FAIL".red(),
+                    highest_vulnerability_count
+                );
+                passed = false;
+            }
+        }
         if summary.wasted_size > highest_wasted_bytes {
             println!(
                 "{}: highest wasted bytes check, highest: {}",
@@ -139,6 +419,64 @@ async fn analyze(image: String, output_file: String) -> Result<(), String> {
             );
             passed = false;
         }
+        if result.incomplete {
+            let failed_layers: Vec<String> = result
+                .layers
+                .iter()
+                .enumerate()
+                .filter(|(_, layer)| layer.error.is_some())
+                .map(|(index, layer)| {
+                    format!("{}({})", index + 1, layer.error.clone().unwrap_or_default())
+                })
+                .collect();
+            if config::get_fail_on_incomplete() {
+                println!(
+                    "{}: incomplete analysis, failed layers: {}",
+                    "FAIL".red(),
+                    failed_layers.join(", ")
+                );
+                passed = false;
+            } else {
+                println!(
+                    "{}: incomplete analysis, failed layers: {}",
+                    "WARN".yellow(),
+                    failed_layers.join(", ")
+                );
+            }
+        }
+        // diff id不一致为完整性校验失败
+        for item in result.diff_id_mismatch_list.iter() {
+            println!(
+                "{}: integrity check, layer {} diff id mismatch, expected: {}, actual: {}",
+                "FAIL".red(),
+                item.layer_index + 1,
+                item.expected,
+                item.actual
+            );
+            passed = false;
+        }
+        for index in summary.unverified_layers.iter() {
+            println!(
+                "{}: integrity check, layer {} is not verified, {}",
+                "WARN".yellow(),
+                index + 1,
+                result.layers[*index]
+                    .diff_id_unverified
+                    .clone()
+                    .unwrap_or_default()
+            );
+        }
+        for index in summary.content_skipped_layers.iter() {
+            println!(
+                "{}: layer {} is listed from toc, {}",
+                "WARN".yellow(),
+                index + 1,
+                result.layers[*index]
+                    .content_skipped
+                    .clone()
+                    .unwrap_or_default()
+            );
+        }
         if summary.wasted_percent > highest_user_wasted_percent {
             println!(
                 "{}: highest user wasted percent check, highest: {:.2}",
@@ -162,6 +500,167 @@ async fn analyze(image: String, output_file: String) -> Result<(), String> {
     Ok(())
 }
 
+// 生成镜像的sbom（错误直接以字符串返回）
+async fn sbom(
+    image: String,
+    base: String,
+    format: String,
+    output_file: String,
+) -> Result<(), String> {
+    clear_blob_files().await.map_err(|item| item.to_string())?;
+    let mut image_info = parse_image_info(&image);
+    image_info.base = base;
+    let result = analyze_docker_image(image_info)
+        .await
+        .map_err(|item| item.to_string())?;
+    let document = to_sbom_document(&format, &image, &result.sbom)
+        .ok_or_else(|| format!("unsupported sbom format {format}, cyclonedx or spdx"))?;
+    let content = serde_json::to_string_pretty(&document).map_err(|err| err.to_string())?;
+    if output_file.is_empty() {
+        println!("{content}");
+    } else {
+        fs::write(output_file, content).map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+// 对比两个镜像（错误直接以字符串返回）
+async fn diff(
+    before: String,
+    after: String,
+    base: String,
+    output_file: String,
+    reproducible: bool,
+    source_date_epoch: Option<i64>,
+) -> Result<(), String> {
+    clear_blob_files().await.map_err(|item| item.to_string())?;
+    let mut before_info = parse_image_info(&before);
+    before_info.base = base.clone();
+    before_info.source_date_epoch = source_date_epoch;
+    let before_result = analyze_docker_image(before_info)
+        .await
+        .map_err(|item| item.to_string())?;
+    let mut after_info = parse_image_info(&after);
+    after_info.base = base;
+    after_info.source_date_epoch = source_date_epoch;
+    let after_result = analyze_docker_image(after_info)
+        .await
+        .map_err(|item| item.to_string())?;
+    let result = diff_image(&before_result, &after_result);
+    let before_layer_count = before_result
+        .layers
+        .iter()
+        .filter(|layer| !layer.empty)
+        .count();
+    if is_ci() || !output_file.is_empty() {
+        let summary = &result.summary;
+        let format_delta = |delta: i64| -> String {
+            let size = ByteSize(delta.unsigned_abs());
+            if delta < 0 {
+                format!("-{size}")
+            } else {
+                format!("+{size}")
+            }
+        };
+        println!("{}", "Diff result:".bold().green());
+        println!(
+            "  image size: {} -> {} ({})",
+            ByteSize(summary.before_size),
+            ByteSize(summary.after_size),
+            format_delta(summary.size_delta)
+        );
+        println!(
+            "  total size: {} -> {} ({})",
+            ByteSize(summary.before_total_size),
+            ByteSize(summary.after_total_size),
+            format_delta(summary.total_size_delta)
+        );
+        println!(
+            "  efficiency: {} % -> {} % ({:+})",
+            summary.before_score, summary.after_score, summary.score_delta
+        );
+        println!(
+            "  wasted bytes: {} -> {} ({})",
+            ByteSize(summary.before_wasted_size),
+            ByteSize(summary.after_wasted_size),
+            format_delta(summary.wasted_size_delta)
+        );
+        println!(
+            "  shared layers: {} ({})",
+            result.shared_layer_list.len(),
+            ByteSize(summary.shared_size)
+        );
+        println!(
+            "  files: {} added, {} removed, {} modified, {} metadata only",
+            summary.added_count,
+            summary.removed_count,
+            summary.modified_count,
+            summary.metadata_count
+        );
+        let mut passed = true;
+        // 可重现构建的对比，列出内容一致仅元数据不同的文件
+        if reproducible {
+            for item in result.metadata_file_list.iter() {
+                let values: Vec<String> = item
+                    .fields
+                    .iter()
+                    .map(|field| match field.as_str() {
+                        "mode" => format!("mode {} -> {}", item.before.mode, item.after.mode),
+                        "uid" => format!("uid {} -> {}", item.before.uid, item.after.uid),
+                        "gid" => format!("gid {} -> {}", item.before.gid, item.after.gid),
+                        _ => format!("mtime {} -> {}", item.before.mtime, item.after.mtime),
+                    })
+                    .collect();
+                println!(
+                    "{}: reproducible check, metadata differs: /{} ({})",
+                    "FAIL".red(),
+                    item.path,
+                    values.join(", ")
+                );
+            }
+            for item in result.file_list.iter() {
+                let op = match item.op {
+                    Op::Added => "added",
+                    Op::Removed => "removed",
+                    _ => "modified",
+                };
+                println!(
+                    "{}: reproducible check, content differs: /{} ({op})",
+                    "FAIL".red(),
+                    item.path,
+                );
+            }
+            if !result.is_identical() {
+                println!(
+                    "{}: reproducible check, {} of {} layers are identical",
+                    "FAIL".red(),
+                    result.shared_layer_list.len(),
+                    before_layer_count
+                );
+                passed = false;
+            }
+        }
+        if !output_file.is_empty() {
+            fs::write(
+                output_file,
+                serde_json::to_string(&result).map_err(|err| err.to_string())?,
+            )
+            .map_err(|err| err.to_string())?;
+        }
+        if !passed {
+            return Err("CI check fail".to_string());
+        }
+    } else {
+        let identical = result.is_identical();
+        ui::run_diff_app(result).map_err(|item| item.to_string())?;
+        // 终端模式下两次构建不一致也返回失败
+        if reproducible && !identical {
+            return Err("reproducible check fail".to_string());
+        }
+    }
+    Ok(())
+}
+
 #[tokio::main]
 async fn run() {
     // 启动时确保可以读取配置
@@ -169,9 +668,33 @@ async fn run() {
     let args = Args::parse();
     if args.is_terminal_type() {
         if let Some(value) = args.image {
+            let output_file = args.output_file.unwrap_or_default();
+            let base = args.base.unwrap_or_default();
             TRACE_ID
                 .scope(generate_trace_id(), async {
-                    if let Err(err) = analyze(value, args.output_file.unwrap_or_default()).await {
+                    let result = if let Some(target) = args.diff {
+                        diff(
+                            value,
+                            target,
+                            base,
+                            output_file,
+                            args.reproducible,
+                            args.source_date_epoch,
+                        )
+                        .await
+                    } else if let Some(format) = args.sbom {
+                        sbom(value, base, format, output_file).await
+                    } else {
+                        analyze(
+                            value,
+                            base,
+                            output_file,
+                            args.reproducible,
+                            args.source_date_epoch,
+                        )
+                        .await
+                    };
+                    if let Err(err) = result {
                         error!(err, "analyze image fail");
                         std::process::exit(1)
                     }
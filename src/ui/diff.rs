@@ -0,0 +1,291 @@
+use bytesize::ByteSize;
+use crossterm::{
+    event::{self, Event, KeyCode, KeyModifiers},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use pad::PadStr;
+use ratatui::{prelude::*, widgets::*};
+use std::{error::Error, io};
+
+use super::util;
+use crate::image::{ImageDiffFile, ImageDiffResult, ImageMetadataDiff, Op};
+
+// 大小变化，带正负号
+fn format_size_delta(delta: i64) -> String {
+    let size = ByteSize(delta.unsigned_abs()).to_string();
+    if delta < 0 {
+        format!("-{size}")
+    } else {
+        format!("+{size}")
+    }
+}
+
+fn is_match_mode(mode: u8, item: &ImageDiffFile) -> bool {
+    match mode {
+        1 => item.op == Op::Added,
+        2 => item.op == Op::Removed,
+        3 => item.op == Op::Modified,
+        _ => true,
+    }
+}
+
+fn new_summary_widget(result: &ImageDiffResult) -> Paragraph<'_> {
+    let summary = &result.summary;
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("Before: ", bold),
+            Span::from(result.before.clone()),
+            Span::from("   "),
+            Span::styled("After: ", bold),
+            Span::from(result.after.clone()),
+        ]),
+        Line::from(vec![
+            Span::styled("Image size: ", bold),
+            Span::from(format!(
+                "{} → {} ({})",
+                ByteSize(summary.before_size),
+                ByteSize(summary.after_size),
+                format_size_delta(summary.size_delta)
+            )),
+        ]),
+        Line::from(vec![
+            Span::styled("Total size: ", bold),
+            Span::from(format!(
+                "{} → {} ({})",
+                ByteSize(summary.before_total_size),
+                ByteSize(summary.after_total_size),
+                format_size_delta(summary.total_size_delta)
+            )),
+        ]),
+        Line::from(vec![
+            Span::styled("Efficiency score: ", bold),
+            Span::from(format!(
+                "{} → {} ({:+})",
+                summary.before_score, summary.after_score, summary.score_delta
+            )),
+        ]),
+        Line::from(vec![
+            Span::styled("Wasted space: ", bold),
+            Span::from(format!(
+                "{} → {} ({})",
+                ByteSize(summary.before_wasted_size),
+                ByteSize(summary.after_wasted_size),
+                format_size_delta(summary.wasted_size_delta)
+            )),
+        ]),
+        Line::from(vec![
+            Span::styled("Shared layers: ", bold),
+            Span::from(format!(
+                "{} ({})",
+                result.shared_layer_list.len(),
+                ByteSize(summary.shared_size)
+            )),
+        ]),
+        Line::from(vec![
+            Span::styled("Files: ", bold),
+            Span::styled(
+                format!("{} added", summary.added_count),
+                Style::default().fg(Color::Green),
+            ),
+            Span::from("   "),
+            Span::styled(
+                format!("{} removed", summary.removed_count),
+                Style::default().fg(Color::Red),
+            ),
+            Span::from("   "),
+            Span::styled(
+                format!("{} modified", summary.modified_count),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::from("   "),
+            Span::styled(
+                format!("{} metadata only", summary.metadata_count),
+                Style::default().fg(Color::Cyan),
+            ),
+        ]),
+    ];
+    Paragraph::new(lines).block(util::create_block(" Image Diff "))
+}
+
+fn new_file_list_widget(file_list: &[ImageDiffFile], mode: u8) -> List<'_> {
+    let size_width = 12;
+    let list: Vec<ListItem> = file_list
+        .iter()
+        .filter(|item| is_match_mode(mode, item))
+        .map(|item| {
+            let (flag, color) = match item.op {
+                Op::Added => ("+", Color::Green),
+                Op::Removed => ("-", Color::Red),
+                _ => ("~", Color::Yellow),
+            };
+            let style = Style::default().fg(color);
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    ByteSize(item.before_size)
+                        .to_string()
+                        .pad_to_width_with_alignment(size_width, pad::Alignment::Right),
+                    style,
+                ),
+                Span::styled(
+                    ByteSize(item.after_size)
+                        .to_string()
+                        .pad_to_width_with_alignment(size_width, pad::Alignment::Right),
+                    style,
+                ),
+                Span::styled(
+                    format_size_delta(item.size_delta)
+                        .pad_to_width_with_alignment(size_width, pad::Alignment::Right),
+                    style,
+                ),
+                Span::from("   "),
+                Span::styled(format!("{flag} /{}", item.path), style),
+            ]))
+        })
+        .collect();
+    List::new(list).highlight_style(Style::default().bg(Color::White).fg(Color::Black))
+}
+
+// 内容一致仅元数据不同的文件
+fn new_metadata_list_widget(metadata_file_list: &[ImageMetadataDiff]) -> List<'_> {
+    let fields_width = 36;
+    let list: Vec<ListItem> = metadata_file_list
+        .iter()
+        .map(|item| {
+            let values: Vec<String> = item
+                .fields
+                .iter()
+                .map(|field| match field.as_str() {
+                    "mode" => format!("{} → {}", item.before.mode, item.after.mode),
+                    "uid" => format!("uid {} → {}", item.before.uid, item.after.uid),
+                    "gid" => format!("gid {} → {}", item.before.gid, item.after.gid),
+                    _ => format!("mtime {} → {}", item.before.mtime, item.after.mtime),
+                })
+                .collect();
+            let style = Style::default().fg(Color::Cyan);
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    item.fields
+                        .join(",")
+                        .pad_to_width_with_alignment(fields_width, pad::Alignment::Right),
+                    style,
+                ),
+                Span::from("   "),
+                Span::styled(format!("/{} ({})", item.path, values.join(", ")), style),
+            ]))
+        })
+        .collect();
+    List::new(list).highlight_style(Style::default().bg(Color::White).fg(Color::Black))
+}
+
+fn draw_widgets(f: &mut Frame, result: &ImageDiffResult, mode: u8, state: &mut ListState) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(9), Constraint::Fill(1)].as_ref())
+        .split(f.area());
+    f.render_widget(new_summary_widget(result), chunks[0]);
+
+    let block = util::create_block(" File Changes ");
+    let content_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([Constraint::Length(2), Constraint::Fill(1)].as_ref())
+        .split(chunks[1]);
+    let mode_tips = format!(
+        "Esc|0: All   1: Added   2: Removed   3: Modified   4: Metadata only   |  Current: {}",
+        mode
+    );
+    let columns = if mode == 4 {
+        format!(
+            "{}   Path",
+            "Fields".pad_to_width_with_alignment(36, pad::Alignment::Right)
+        )
+    } else {
+        "      Before       After       Delta   Path".to_string()
+    };
+    let content = Paragraph::new(vec![
+        Line::from(vec![Span::styled(
+            mode_tips,
+            Style::default().add_modifier(Modifier::BOLD),
+        )]),
+        Line::from(vec![Span::from(columns)]),
+    ]);
+    f.render_widget(block, chunks[1]);
+    f.render_widget(content, content_chunks[0]);
+    if mode == 4 {
+        f.render_stateful_widget(
+            new_metadata_list_widget(&result.metadata_file_list),
+            content_chunks[1],
+            state,
+        );
+    } else {
+        f.render_stateful_widget(
+            new_file_list_widget(&result.file_list, mode),
+            content_chunks[1],
+            state,
+        );
+    }
+}
+
+// 展示两个镜像的对比结果
+pub fn run_diff_app(result: ImageDiffResult) -> Result<(), Box<dyn Error>> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    let mut mode = 0;
+    let mut state = ListState::default();
+    state.select(Some(0));
+    loop {
+        terminal.draw(|f| draw_widgets(f, &result, mode, &mut state))?;
+        let count = if mode == 4 {
+            result.metadata_file_list.len()
+        } else {
+            result
+                .file_list
+                .iter()
+                .filter(|item| is_match_mode(mode, item))
+                .count()
+        };
+
+        if let Event::Key(key) = event::read()? {
+            let selected = state.selected().unwrap_or_default();
+            match key.code {
+                // 退出
+                KeyCode::Char('c') => {
+                    if key.modifiers.contains(KeyModifiers::CONTROL) {
+                        break;
+                    }
+                }
+                KeyCode::Char('q') => break,
+                KeyCode::Down => {
+                    if selected + 1 < count {
+                        state.select(Some(selected + 1));
+                    }
+                }
+                KeyCode::Up => state.select(Some(selected.saturating_sub(1))),
+                KeyCode::Char('0') | KeyCode::Esc => mode = 0,
+                KeyCode::Char('1') => mode = 1,
+                KeyCode::Char('2') => mode = 2,
+                KeyCode::Char('3') => mode = 3,
+                KeyCode::Char('4') => mode = 4,
+                _ => continue,
+            }
+            // 切换模式后选择第一个文件
+            if matches!(
+                key.code,
+                KeyCode::Char('0' | '1' | '2' | '3' | '4') | KeyCode::Esc
+            ) {
+                state.select(Some(0));
+            }
+        }
+    }
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    Ok(())
+}
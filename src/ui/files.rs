@@ -11,6 +11,10 @@ pub struct FilesWidgetOption {
     pub selected_layer: usize,
     pub area: Rect,
     pub mode: u8,
+    // 是否展示叠加后的文件系统
+    pub merged: bool,
+    // 展示该范围内layer的变化
+    pub range: Option<(usize, usize)>,
 }
 
 pub struct FilesWidget<'a> {
@@ -29,7 +33,8 @@ pub struct FilesWidget<'a> {
 }
 
 fn is_modified_or_removed(item: &FileTreeItem) -> bool {
-    if item.op == Op::Removed || item.op == Op::Modified {
+    // 叠加视图中当前层修改的文件标记为Added
+    if item.op == Op::Removed || item.op == Op::Modified || item.op == Op::Added {
         return true;
     }
     // 如果子元素有此类型，也要展示
@@ -79,6 +84,8 @@ fn add_to_file_tree_view(
     let max = items.len();
 
     for (index, item) in items.iter().enumerate() {
+        // 保持原有写法，新版本clippy新增的lint
+        #[allow(clippy::collapsible_match)]
         match mode {
             // 只展示更新与删除
             1 => {
@@ -98,6 +105,7 @@ fn add_to_file_tree_view(
         match item.op {
             Op::Modified => style = style.fg(Color::Yellow),
             Op::Removed => style = style.fg(Color::Red),
+            Op::Added => style = style.fg(Color::Green),
             _ => {}
         }
         let id = format!("{}:{}", item.uid, item.gid);
@@ -107,6 +115,12 @@ fn add_to_file_tree_view(
         if !item.link.is_empty() {
             name = format!("{name} → {}", item.link);
         }
+        // 叠加视图中展示文件所属的层
+        if item.children.is_empty() {
+            if let Some(layer_index) = item.layer_index {
+                name = format!("{name} [L{}]", layer_index + 1);
+            }
+        }
         list.push(ListItem::new(Line::from(vec![
             Span::styled(get_file_mode_str(&item.mode), style),
             space_span.clone(),
@@ -140,10 +154,15 @@ pub fn new_files_widget(
     file_tree_list: &[Vec<FileTreeItem>],
     opt: FilesWidgetOption,
 ) -> FilesWidget<'_> {
-    // TODO 如何调整生命周期
-    let mut title = " Current Layer Contents ";
+    let mut title = if let Some((start, end)) = opt.range {
+        format!(" Layer {} - {} Changes ", start + 1, end + 1)
+    } else if opt.merged {
+        " Aggregated Layer Contents ".to_string()
+    } else {
+        " Current Layer Contents ".to_string()
+    };
     if opt.is_active {
-        title = " ● Current Layer Contents ";
+        title = format!(" ●{title}");
     }
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -154,7 +173,7 @@ pub fn new_files_widget(
     let space_span = Span::from("   ");
     let name_list = ["Permission", " UID:GID ", "     Size", "FileTree"];
     let mode_tips = format!(
-        "Esc|0: All   1: Modified/Removed   2: File >= 1MB   |  Current: {}",
+        "Esc|0: All   1: Modified/Removed   2: File >= 1MB   a: Aggregated   m: Mark Range   |  Current: {}",
         opt.mode
     );
     let content = Paragraph::new(vec![
@@ -3,7 +3,12 @@ use pad::PadStr;
 use ratatui::{prelude::*, widgets::*};
 
 use super::util;
-use crate::image::DockerAnalyzeSummary;
+use crate::image::{
+    BaseImageInfo, DiffIdMismatchInfo, DockerAnalyzeSummary, DuplicateFileGroup, ElfBinaryInfo,
+    FileTypeSize, GhostFileInfo, ImageExtraInfo, ImageHealthcheck, LanguageComponent, LintIssue,
+    PackageCacheInfo, PermissionFinding, ReproducibilityReport, SecretFinding,
+    VulnerabilityFinding,
+};
 
 pub struct ImageDetailWidget<'a> {
     pub widget: Paragraph<'a>,
@@ -16,6 +21,105 @@ pub struct ImageDetailWidgetOption {
     pub total_size: u64,
     pub size: u64,
     pub summary: DockerAnalyzeSummary,
+    pub duplicate_file_list: Vec<DuplicateFileGroup>,
+    pub ghost_file_list: Vec<GhostFileInfo>,
+    // 解析失败的layer
+    pub failed_layers: Vec<usize>,
+    // 告警信息
+    pub warning_list: Vec<String>,
+    // diff id校验失败的layer
+    pub diff_id_mismatch_list: Vec<DiffIdMismatchInfo>,
+    // 基础镜像
+    pub base_image: Option<BaseImageInfo>,
+    // 包管理工具的缓存
+    pub package_cache_list: Vec<PackageCacheInfo>,
+    // 不符合检测规则的问题
+    pub lint_issue_list: Vec<LintIssue>,
+    // 敏感信息
+    pub secret_list: Vec<SecretFinding>,
+    // 语言生态的组件
+    pub component_list: Vec<LanguageComponent>,
+    // 匹配的漏洞
+    pub vulnerability_list: Vec<VulnerabilityFinding>,
+    // elf二进制文件
+    pub elf_binary_list: Vec<ElfBinaryInfo>,
+    // 按文件类型汇总的大小
+    pub file_type_list: Vec<FileTypeSize>,
+    // 权限与属主的问题
+    pub permission_finding_list: Vec<PermissionFinding>,
+    // 镜像的运行配置
+    pub image_config: ImageExtraInfo,
+    // 影响可重现构建的问题
+    pub reproducibility: ReproducibilityReport,
+}
+
+// 健康检查的描述，如：CMD curl -f localhost (interval: 30s, retries: 3)
+fn format_healthcheck(healthcheck: &ImageHealthcheck) -> String {
+    let mut options = vec![];
+    for (name, value) in [
+        ("interval", healthcheck.interval),
+        ("timeout", healthcheck.timeout),
+        ("start period", healthcheck.start_period),
+        ("start interval", healthcheck.start_interval),
+    ] {
+        if let Some(value) = value.filter(|value| *value > 0) {
+            options.push(format!(
+                "{name}: {:?}",
+                std::time::Duration::from_nanos(value as u64)
+            ));
+        }
+    }
+    if let Some(retries) = healthcheck.retries.filter(|value| *value > 0) {
+        options.push(format!("retries: {retries}"));
+    }
+    let mut desc = healthcheck.test.join(" ");
+    if !options.is_empty() {
+        desc += &format!(" ({})", options.join(", "));
+    }
+    desc
+}
+
+// 镜像运行配置中有值的项
+fn get_image_config_lines<'a>(config: &ImageExtraInfo) -> Vec<Line<'a>> {
+    let join = |value: &Option<Vec<String>>| value.clone().unwrap_or_default().join(" ");
+    let values = [
+        ("Entrypoint: ", join(&config.entrypoint)),
+        ("Cmd: ", join(&config.cmd)),
+        ("User: ", config.user.clone().unwrap_or_default()),
+        (
+            "Working dir: ",
+            config.working_dir.clone().unwrap_or_default(),
+        ),
+        ("Exposed ports: ", config.get_exposed_ports().join(", ")),
+        ("Volumes: ", config.get_volumes().join(", ")),
+        (
+            "Healthcheck: ",
+            config
+                .healthcheck
+                .as_ref()
+                .map(format_healthcheck)
+                .unwrap_or_default(),
+        ),
+        (
+            "Stop signal: ",
+            config.stop_signal.clone().unwrap_or_default(),
+        ),
+        (
+            "OnBuild: ",
+            config.on_build.clone().unwrap_or_default().join("; "),
+        ),
+        ("Shell: ", join(&config.shell)),
+    ];
+    values
+        .into_iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(name, value)| {
+            Line::from(vec![
+                Span::styled(name, Style::default().add_modifier(Modifier::BOLD)),
+                Span::from(value),
+            ])
+        })
+        .collect()
 }
 
 pub fn new_image_detail_widget<'a>(opt: ImageDetailWidgetOption) -> ImageDetailWidget<'a> {
@@ -83,6 +187,34 @@ pub fn new_image_detail_widget<'a>(opt: ImageDetailWidgetOption) -> ImageDetailW
             ),
             Span::from(ByteSize(wasted_size).to_string()),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "Duplicate files space: ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::from(ByteSize(opt.summary.duplicate_size).to_string()),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "Added then removed space: ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::from(ByteSize(opt.summary.ghost_size).to_string()),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "Package cache space: ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::from(ByteSize(opt.summary.package_cache_size).to_string()),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "Estimated strip savings: ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::from(ByteSize(opt.summary.strip_savings).to_string()),
+        ]),
         Line::from(vec![
             Span::styled(
                 "Image efficiency score: ",
@@ -90,6 +222,165 @@ pub fn new_image_detail_widget<'a>(opt: ImageDetailWidgetOption) -> ImageDetailW
             ),
             Span::from(format!("{score} %")),
         ]),
+    ];
+    // 以zstd压缩layer后拉取时预计可节省的空间
+    if !opt.summary.zstd_savings_list.is_empty() {
+        let savings: Vec<String> = opt
+            .summary
+            .zstd_savings_list
+            .iter()
+            .map(|item| {
+                let sign = if item.savings < 0 { "-" } else { "" };
+                format!(
+                    "-{} {sign}{}",
+                    item.level,
+                    ByteSize(item.savings.unsigned_abs())
+                )
+            })
+            .collect();
+        spans_list.push(Line::from(vec![
+            Span::styled(
+                "Estimated zstd savings: ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::from(savings.join(", ")),
+        ]));
+    }
+    spans_list.append(&mut get_image_config_lines(&opt.image_config));
+    if !opt.vulnerability_list.is_empty() {
+        spans_list.push(Line::from(vec![
+            Span::styled(
+                "Vulnerabilities: ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                opt.summary.vulnerability_count.to_string(),
+                Style::default().fg(Color::Red),
+            ),
+        ]));
+    }
+    if let Some(ref base_image) = opt.base_image {
+        spans_list.append(&mut vec![
+            Line::from(vec![
+                Span::styled(
+                    "Base image: ",
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::from(format!(
+                    "{} ({} layers, {} / {})",
+                    base_image.name,
+                    base_image.layer_count,
+                    ByteSize(base_image.total_size),
+                    ByteSize(base_image.size)
+                )),
+            ]),
+            Line::from(vec![
+                Span::styled(
+                    "Application layers size: ",
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::from(format!(
+                    "{} / {}",
+                    ByteSize(base_image.app_total_size),
+                    ByteSize(base_image.app_size)
+                )),
+            ]),
+        ]);
+    }
+    if !opt.failed_layers.is_empty() {
+        let failed_layers: Vec<String> = opt
+            .failed_layers
+            .iter()
+            .map(|index| (index + 1).to_string())
+            .collect();
+        spans_list.push(Line::from(vec![Span::styled(
+            format!(
+                "Incomplete analysis, failed layers: {}",
+                failed_layers.join(", ")
+            ),
+            Style::default().add_modifier(Modifier::BOLD).fg(Color::Red),
+        )]));
+    }
+    for item in opt.diff_id_mismatch_list.iter() {
+        spans_list.push(Line::from(vec![Span::styled(
+            format!(
+                "Integrity check fail, layer {} diff id mismatch, expected: {}, actual: {}",
+                item.layer_index + 1,
+                item.expected,
+                item.actual
+            ),
+            Style::default().add_modifier(Modifier::BOLD).fg(Color::Red),
+        )]));
+    }
+    if !opt.summary.unverified_layers.is_empty() {
+        let unverified_layers: Vec<String> = opt
+            .summary
+            .unverified_layers
+            .iter()
+            .map(|index| (index + 1).to_string())
+            .collect();
+        spans_list.push(Line::from(vec![Span::styled(
+            format!(
+                "Integrity check skipped, unverified layers: {}",
+                unverified_layers.join(", ")
+            ),
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .fg(Color::Yellow),
+        )]));
+    }
+    if !opt.summary.content_skipped_layers.is_empty() {
+        let content_skipped_layers: Vec<String> = opt
+            .summary
+            .content_skipped_layers
+            .iter()
+            .map(|index| (index + 1).to_string())
+            .collect();
+        spans_list.push(Line::from(vec![Span::styled(
+            format!(
+                "ELF binaries and language components are not analyzed(listed from toc), layers: {}",
+                content_skipped_layers.join(", ")
+            ),
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .fg(Color::Yellow),
+        )]));
+    }
+    for issue in opt.lint_issue_list.iter() {
+        let mut message = format!("Lint {}: {}", issue.rule, issue.message);
+        if !issue.paths.is_empty() {
+            message += &format!(", {} files: {}", issue.count, issue.paths.join(", "));
+        }
+        let color = if issue.is_error() {
+            Color::Red
+        } else {
+            Color::Yellow
+        };
+        spans_list.push(Line::from(vec![Span::styled(
+            message,
+            Style::default().fg(color),
+        )]));
+    }
+    for finding in opt.secret_list.iter() {
+        let layer = finding
+            .layer_index
+            .map(|index| format!("layer {}, ", index + 1))
+            .unwrap_or_default();
+        spans_list.push(Line::from(vec![Span::styled(
+            format!(
+                "Secret found, {layer}{}, {}: {}",
+                finding.path, finding.rule, finding.redacted
+            ),
+            Style::default().add_modifier(Modifier::BOLD).fg(Color::Red),
+        )]));
+    }
+    for warning in opt.warning_list.iter() {
+        spans_list.push(Line::from(vec![Span::styled(
+            format!("Warning: {warning}"),
+            Style::default().fg(Color::Yellow),
+        )]));
+    }
+    spans_list.append(&mut vec![
         Line::from(vec![]),
         Line::from(vec![
             Span::styled(headers[0], Style::default().add_modifier(Modifier::BOLD)),
@@ -98,7 +389,7 @@ pub fn new_image_detail_widget<'a>(opt: ImageDetailWidgetOption) -> ImageDetailW
             space_span.clone(),
             Span::styled(headers[2], Style::default().add_modifier(Modifier::BOLD)),
         ]),
-    ];
+    ]);
 
     let count_pad_width = headers[0].len();
     let size_pad_width = headers[1].len();
@@ -118,6 +409,353 @@ pub fn new_image_detail_widget<'a>(opt: ImageDetailWidgetOption) -> ImageDetailW
         ]))
     }
 
+    // 按文件类型汇总的大小
+    if !opt.file_type_list.is_empty() {
+        spans_list.push(Line::from(vec![]));
+        spans_list.push(Line::from(vec![
+            Span::styled(headers[0], Style::default().add_modifier(Modifier::BOLD)),
+            space_span.clone(),
+            Span::styled(headers[1], Style::default().add_modifier(Modifier::BOLD)),
+            space_span.clone(),
+            Span::styled("File Types", Style::default().add_modifier(Modifier::BOLD)),
+        ]));
+    }
+    for item in opt.file_type_list.iter() {
+        let count_str = format!("{}", item.count)
+            .pad_to_width_with_alignment(count_pad_width, pad::Alignment::Right);
+        let size_str = ByteSize(item.size)
+            .to_string()
+            .pad_to_width_with_alignment(size_pad_width, pad::Alignment::Right);
+        let percent = (item.size * 100)
+            .checked_div(total_size)
+            .unwrap_or_default();
+        spans_list.push(Line::from(vec![
+            Span::from(count_str),
+            space_span.clone(),
+            Span::from(size_str),
+            space_span.clone(),
+            Span::from(format!("{} ({percent}%)", item.file_type)),
+        ]))
+    }
+
+    // 内容相同的文件
+    if !opt.duplicate_file_list.is_empty() {
+        spans_list.push(Line::from(vec![]));
+        spans_list.push(Line::from(vec![
+            Span::styled(headers[0], Style::default().add_modifier(Modifier::BOLD)),
+            space_span.clone(),
+            Span::styled(
+                "Dup Space".pad_to_width_with_alignment(size_pad_width, pad::Alignment::Right),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            space_span.clone(),
+            Span::styled(
+                "Duplicate Files",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+        ]));
+    }
+    for duplicate in opt.duplicate_file_list.iter() {
+        let count_str = format!("{}", duplicate.files.len())
+            .pad_to_width_with_alignment(count_pad_width, pad::Alignment::Right);
+        let size_str = ByteSize(duplicate.wasted_size)
+            .to_string()
+            .pad_to_width_with_alignment(size_pad_width, pad::Alignment::Right);
+        let paths: Vec<String> = duplicate
+            .files
+            .iter()
+            .map(|file| format!("/{}", file.info.path))
+            .collect();
+        spans_list.push(Line::from(vec![
+            Span::from(count_str),
+            space_span.clone(),
+            Span::from(size_str),
+            space_span.clone(),
+            Span::from(paths.join(", ")),
+        ]))
+    }
+
+    // 添加后又删除的文件
+    if !opt.ghost_file_list.is_empty() {
+        spans_list.push(Line::from(vec![]));
+        spans_list.push(Line::from(vec![
+            Span::styled("Layer", Style::default().add_modifier(Modifier::BOLD)),
+            space_span.clone(),
+            Span::styled(headers[1], Style::default().add_modifier(Modifier::BOLD)),
+            space_span.clone(),
+            Span::styled(
+                "Added Then Removed Files",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+        ]));
+    }
+    for ghost in opt.ghost_file_list.iter() {
+        let layer_str = format!(
+            "{}→{}",
+            ghost.added_layer_index + 1,
+            ghost.removed_layer_index + 1
+        )
+        .pad_to_width_with_alignment(count_pad_width, pad::Alignment::Right);
+        let size_str = ByteSize(ghost.size)
+            .to_string()
+            .pad_to_width_with_alignment(size_pad_width, pad::Alignment::Right);
+        spans_list.push(Line::from(vec![
+            Span::from(layer_str),
+            space_span.clone(),
+            Span::from(size_str),
+            space_span.clone(),
+            Span::from(format!("/{}", ghost.path)),
+        ]))
+    }
+
+    // 包管理工具的缓存
+    if !opt.package_cache_list.is_empty() {
+        spans_list.push(Line::from(vec![]));
+        spans_list.push(Line::from(vec![
+            Span::styled("Layer", Style::default().add_modifier(Modifier::BOLD)),
+            space_span.clone(),
+            Span::styled(headers[1], Style::default().add_modifier(Modifier::BOLD)),
+            space_span.clone(),
+            Span::styled(
+                "Package Caches",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+        ]));
+    }
+    for cache in opt.package_cache_list.iter() {
+        let layer_str = format!("{}", cache.layer_index + 1)
+            .pad_to_width_with_alignment(count_pad_width, pad::Alignment::Right);
+        let size_str = ByteSize(cache.size)
+            .to_string()
+            .pad_to_width_with_alignment(size_pad_width, pad::Alignment::Right);
+        spans_list.push(Line::from(vec![
+            Span::from(layer_str),
+            space_span.clone(),
+            Span::from(size_str),
+            space_span.clone(),
+            Span::from(format!("{} ({})", cache.path, cache.ecosystem)),
+        ]))
+    }
+
+    // 未strip的二进制文件
+    let unstripped_list: Vec<&ElfBinaryInfo> = opt
+        .elf_binary_list
+        .iter()
+        .filter(|item| item.strip_savings() > 0)
+        .collect();
+    if !unstripped_list.is_empty() {
+        spans_list.push(Line::from(vec![]));
+        spans_list.push(Line::from(vec![
+            Span::styled("Layer", Style::default().add_modifier(Modifier::BOLD)),
+            space_span.clone(),
+            Span::styled(
+                "Savings".pad_to_width_with_alignment(size_pad_width, pad::Alignment::Right),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            space_span.clone(),
+            Span::styled(
+                "Unstripped Binaries",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+        ]));
+    }
+    for binary in unstripped_list {
+        let layer_str = format!("{}", binary.layer_index + 1)
+            .pad_to_width_with_alignment(count_pad_width, pad::Alignment::Right);
+        let size_str = ByteSize(binary.strip_savings())
+            .to_string()
+            .pad_to_width_with_alignment(size_pad_width, pad::Alignment::Right);
+        let mut message = format!("{} ({}", binary.path, binary.linkage);
+        if !binary.needed.is_empty() {
+            message += &format!(", needed: {}", binary.needed.join(" "));
+        }
+        message += ")";
+        spans_list.push(Line::from(vec![
+            Span::from(layer_str),
+            space_span.clone(),
+            Span::from(size_str),
+            space_span.clone(),
+            Span::from(message),
+        ]))
+    }
+
+    // 权限与属主的问题
+    if !opt.permission_finding_list.is_empty() {
+        spans_list.push(Line::from(vec![]));
+        spans_list.push(Line::from(vec![
+            Span::styled("Layer", Style::default().add_modifier(Modifier::BOLD)),
+            space_span.clone(),
+            Span::styled(
+                "Mode".pad_to_width_with_alignment(size_pad_width, pad::Alignment::Right),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            space_span.clone(),
+            Span::styled(
+                "Permission Issues",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+        ]));
+    }
+    for finding in opt.permission_finding_list.iter() {
+        let layer_str = format!("{}", finding.layer_index + 1)
+            .pad_to_width_with_alignment(count_pad_width, pad::Alignment::Right);
+        let mode_str = finding
+            .mode
+            .pad_to_width_with_alignment(size_pad_width, pad::Alignment::Right);
+        spans_list.push(Line::from(vec![
+            Span::from(layer_str),
+            space_span.clone(),
+            Span::from(mode_str),
+            space_span.clone(),
+            Span::from(format!(
+                "{} ({}, {}:{})",
+                finding.path, finding.kind, finding.uid, finding.gid
+            )),
+        ]))
+    }
+
+    // 影响可重现构建的文件、tar header与build id
+    let reproducibility = &opt.reproducibility;
+    if reproducibility.has_issue() || !reproducibility.build_id_list.is_empty() {
+        let mut title = "Reproducibility".to_string();
+        if let Some(epoch) = reproducibility.source_date_epoch {
+            title += &format!(
+                " (SOURCE_DATE_EPOCH: {epoch}, from {})",
+                reproducibility.source_date_epoch_from
+            );
+        }
+        spans_list.push(Line::from(vec![]));
+        spans_list.push(Line::from(vec![
+            Span::styled("Layer", Style::default().add_modifier(Modifier::BOLD)),
+            space_span.clone(),
+            Span::styled(
+                "Kind".pad_to_width_with_alignment(size_pad_width, pad::Alignment::Right),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            space_span.clone(),
+            Span::styled(title, Style::default().add_modifier(Modifier::BOLD)),
+        ]));
+    }
+    let mut reproducibility_lines = vec![];
+    for file in reproducibility.mtime_file_list.iter() {
+        reproducibility_lines.push((
+            file.layer_index,
+            "mtime",
+            format!("{} ({})", file.path, file.mtime),
+        ));
+    }
+    for file in reproducibility.owner_file_list.iter() {
+        reproducibility_lines.push((
+            file.layer_index,
+            "owner",
+            format!("{} ({}:{})", file.path, file.uid, file.gid),
+        ));
+    }
+    for item in reproducibility.tar_header_list.iter() {
+        reproducibility_lines.push((
+            item.layer_index,
+            "tar header",
+            format!(
+                "{} unsorted, formats {}, {} with atime/ctime, {} with user/group name",
+                item.unsorted_count,
+                item.formats.join("/"),
+                item.access_time_count,
+                item.owner_name_count
+            ),
+        ));
+    }
+    for item in reproducibility.build_id_list.iter() {
+        reproducibility_lines.push((
+            item.layer_index,
+            "build id",
+            format!("{} ({})", item.path, item.build_id),
+        ));
+    }
+    for (layer_index, kind, message) in reproducibility_lines {
+        let layer_str = format!("{}", layer_index + 1)
+            .pad_to_width_with_alignment(count_pad_width, pad::Alignment::Right);
+        spans_list.push(Line::from(vec![
+            Span::from(layer_str),
+            space_span.clone(),
+            Span::from(kind.pad_to_width_with_alignment(size_pad_width, pad::Alignment::Right)),
+            space_span.clone(),
+            Span::from(message),
+        ]))
+    }
+
+    // 按layer列出的漏洞
+    if !opt.vulnerability_list.is_empty() {
+        spans_list.push(Line::from(vec![]));
+        spans_list.push(Line::from(vec![
+            Span::styled("Layer", Style::default().add_modifier(Modifier::BOLD)),
+            space_span.clone(),
+            Span::styled(
+                "Severity".pad_to_width_with_alignment(size_pad_width, pad::Alignment::Right),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            space_span.clone(),
+            Span::styled(
+                "Vulnerabilities",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+        ]));
+    }
+    for finding in opt.vulnerability_list.iter() {
+        let layer_str = format!("{}", finding.layer_index + 1)
+            .pad_to_width_with_alignment(count_pad_width, pad::Alignment::Right);
+        let severity_str = finding
+            .severity
+            .pad_to_width_with_alignment(size_pad_width, pad::Alignment::Right);
+        let mut message = format!("{} {}@{}", finding.id, finding.package, finding.version);
+        if !finding.fixed_version.is_empty() {
+            message += &format!(" → {}", finding.fixed_version);
+        }
+        spans_list.push(Line::from(vec![
+            Span::from(layer_str),
+            space_span.clone(),
+            Span::from(severity_str),
+            space_span.clone(),
+            Span::from(message),
+        ]))
+    }
+
+    // 占用空间的语言组件（嵌入二进制的依赖不展示）
+    let component_list: Vec<&LanguageComponent> = opt
+        .component_list
+        .iter()
+        .filter(|item| item.size > 0)
+        .collect();
+    if !component_list.is_empty() {
+        spans_list.push(Line::from(vec![]));
+        spans_list.push(Line::from(vec![
+            Span::styled("Layer", Style::default().add_modifier(Modifier::BOLD)),
+            space_span.clone(),
+            Span::styled(headers[1], Style::default().add_modifier(Modifier::BOLD)),
+            space_span.clone(),
+            Span::styled(
+                "Language Components",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+        ]));
+    }
+    for component in component_list {
+        let layer_str = format!("{}", component.layer_index + 1)
+            .pad_to_width_with_alignment(count_pad_width, pad::Alignment::Right);
+        let size_str = ByteSize(component.size)
+            .to_string()
+            .pad_to_width_with_alignment(size_pad_width, pad::Alignment::Right);
+        spans_list.push(Line::from(vec![
+            Span::from(layer_str),
+            space_span.clone(),
+            Span::from(size_str),
+            space_span.clone(),
+            Span::from(format!(
+                "{}@{} ({}) {}",
+                component.name, component.version, component.ecosystem, component.path
+            )),
+        ]))
+    }
+
     let widget = Paragraph::new(spans_list).block(util::create_block(" Image Details "));
     ImageDetailWidget { widget }
 }
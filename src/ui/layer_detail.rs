@@ -1,3 +1,4 @@
+use bytesize::ByteSize;
 use chrono::{DateTime, Local, TimeZone};
 use ratatui::{prelude::*, widgets::*};
 
@@ -16,7 +17,21 @@ pub struct DetailWidgetOption {
 // 创建layer详细信息的widget
 pub fn new_layer_detail_widget(layer: &ImageLayer, opt: DetailWidgetOption) -> DetailWidget<'_> {
     let cmd = layer.cmd.clone();
-    let detail_word_width = util::get_width(&cmd);
+    let mut detail_word_width = util::get_width(&cmd);
+    // 支持懒加载的layer，展示toc信息
+    let mut toc_spans = vec![];
+    if let Some(ref toc) = layer.toc {
+        let toc_desc = format!(
+            "{}, {} entries, {} chunks",
+            toc.format, toc.entry_count, toc.chunk_count
+        );
+        detail_word_width += util::get_width(&toc_desc);
+        toc_spans.push(Span::styled(
+            "Toc:",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        toc_spans.push(Span::from(toc_desc));
+    }
     let mut create_at = layer.created.clone();
     if let Ok(value) = DateTime::parse_from_rfc3339(&layer.created) {
         create_at = Local
@@ -26,15 +41,63 @@ pub fn new_layer_detail_widget(layer: &ImageLayer, opt: DetailWidgetOption) -> D
             .to_rfc3339();
     };
 
-    let paragraph = Paragraph::new(Line::from(vec![
+    let mut spans = vec![
         Span::styled("Created:", Style::default().add_modifier(Modifier::BOLD)),
         Span::from(create_at),
-        Span::styled("Command:", Style::default().add_modifier(Modifier::BOLD)),
-        Span::from(cmd),
-    ]))
-    .block(util::create_block(" Layer Details "))
-    .alignment(Alignment::Left)
-    .wrap(Wrap { trim: true });
+    ];
+    spans.append(&mut toc_spans);
+    // 占用空间最多的文件类型
+    if !layer.file_type_list.is_empty() {
+        let types: Vec<String> = layer
+            .file_type_list
+            .iter()
+            .take(5)
+            .map(|item| format!("{} {}", item.file_type, ByteSize(item.size)))
+            .collect();
+        let types = types.join(", ");
+        detail_word_width += util::get_width(&types);
+        spans.push(Span::styled(
+            "Types:",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::from(types));
+    }
+    // 压缩率以及以zstd压缩后预计的大小
+    if let Some(ref compression) = layer.compression {
+        let mut desc = format!("{:.1} %", compression.ratio * 100.0);
+        if compression.uncompressed {
+            desc += " uncompressed";
+        }
+        if compression.precompressed {
+            desc += " precompressed";
+        }
+        for estimate in compression.zstd_estimate_list.iter() {
+            desc += &format!(", zstd -{} {}", estimate.level, ByteSize(estimate.size));
+        }
+        detail_word_width += util::get_width(&desc);
+        spans.push(Span::styled(
+            "Compression:",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::from(desc));
+    }
+    if let Some(ref err) = layer.error {
+        detail_word_width += util::get_width(err);
+        spans.push(Span::styled(
+            "Error:",
+            Style::default().add_modifier(Modifier::BOLD).fg(Color::Red),
+        ));
+        spans.push(Span::styled(err.clone(), Style::default().fg(Color::Red)));
+    }
+    spans.push(Span::styled(
+        "Command:",
+        Style::default().add_modifier(Modifier::BOLD),
+    ));
+    spans.push(Span::from(cmd));
+    let paragraph = Paragraph::new(Line::from(spans))
+        .block(util::create_block(" Layer Details "))
+        .alignment(Alignment::Left)
+        .wrap(Wrap { trim: true });
     // 拆分左侧栏
     let mut detail_height = detail_word_width / opt.width;
     if detail_word_width.is_multiple_of(opt.width) {
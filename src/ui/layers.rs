@@ -13,6 +13,8 @@ pub struct LayersWidget<'a> {
 pub struct LayersWidgetOption {
     pub is_active: bool,
     pub selected_layer: usize,
+    // 标记的layer范围
+    pub range: Option<(usize, usize)>,
 }
 // 创建layer列表的widget
 pub fn new_layers_widget<'a>(layers: &[ImageLayer], opt: LayersWidgetOption) -> LayersWidget<'a> {
@@ -20,9 +22,29 @@ pub fn new_layers_widget<'a>(layers: &[ImageLayer], opt: LayersWidgetOption) ->
     let mut row_data_list = vec![];
     // 生成表格数据，并计算每列最大宽度
     for (index, item) in layers.iter().enumerate() {
-        let no = format!("{}", index + 1);
+        let mut no = format!("{}", index + 1);
+        // 标记范围内的layer
+        if let Some((start, end)) = opt.range {
+            if index >= start && index <= end {
+                no = format!("*{no}");
+            }
+        }
         // TODO 是否调整为1024
-        let arr = vec![no, ByteSize(item.size).to_string(), item.cmd.clone()];
+        let mut cmd = item.cmd.clone();
+        // 无法分析的layer标记原因
+        if let Some(ref reason) = item.unsupported {
+            cmd = format!("[{reason}] {cmd}");
+        }
+        if item.error.is_some() {
+            cmd = format!("[failed] {cmd}");
+        }
+        if item.diff_id_verified == Some(false) {
+            cmd = format!("[diff id mismatch] {cmd}");
+        }
+        if item.diff_id_unverified.is_some() {
+            cmd = format!("[unverified] {cmd}");
+        }
+        let arr = vec![no, ByteSize(item.size).to_string(), cmd];
         for (i, value) in arr.iter().enumerate() {
             if row_max_counts[i] < value.len() {
                 row_max_counts[i] = value.len()
@@ -46,6 +68,13 @@ pub fn new_layers_widget<'a>(layers: &[ImageLayer], opt: LayersWidgetOption) ->
             }
         }
         let mut style = Style::default();
+        // 解析失败的layer
+        if layers[index].error.is_some() || layers[index].diff_id_verified == Some(false) {
+            style = style.fg(Color::Red);
+        } else if layers[index].base {
+            // 基础镜像的layer
+            style = style.fg(Color::DarkGray);
+        }
         if index == opt.selected_layer {
             style = style.bg(Color::White).fg(Color::Black);
         }
@@ -1,5 +1,11 @@
 use self::image_detail::ImageDetailWidgetOption;
-use crate::image::{DockerAnalyzeResult, DockerAnalyzeSummary, FileTreeItem, ImageLayer};
+use crate::image::{
+    build_merged_file_tree, diff_file_tree, BaseImageInfo, DiffIdMismatchInfo, DockerAnalyzeResult,
+    DockerAnalyzeSummary, DuplicateFileGroup, ElfBinaryInfo, FileTreeItem, FileTypeSize,
+    GhostFileInfo, ImageExtraInfo, ImageFileInfo, ImageLayer, LanguageComponent, LintIssue,
+    PackageCacheInfo, PermissionFinding, ReproducibilityReport, SecretFinding,
+    VulnerabilityFinding,
+};
 use crossterm::{
     event::{self, Event, KeyCode, KeyModifiers},
     execute,
@@ -12,12 +18,15 @@ use std::sync::atomic;
 use std::sync::mpsc::sync_channel;
 use std::{error::Error, io};
 
+mod diff;
 mod files;
 mod image_detail;
 mod layer_detail;
 mod layers;
 mod util;
 
+pub use diff::run_diff_app;
+
 #[derive(Default, Debug, Clone)]
 struct WidgetState {
     name: String,
@@ -35,6 +44,18 @@ struct WidgetState {
     layers: Vec<ImageLayer>,
     // 每层对应的文件树
     file_tree_list: Vec<Vec<FileTreeItem>>,
+    // 每层的文件，用于生成叠加后的文件树
+    layer_file_list: Vec<Vec<ImageFileInfo>>,
+    // 叠加后的文件树（仅保存当前展示的层）
+    merged_layer: Option<usize>,
+    merged_file_tree: Vec<FileTreeItem>,
+    // 是否展示叠加后的文件树
+    merged: bool,
+    // 标记的起始与结束layer
+    range_start: Option<usize>,
+    range_end: Option<usize>,
+    // 标记范围内layer的变化
+    range_file_tree: Vec<FileTreeItem>,
     // 文件列表的状态
     files_state: ListState,
     // 文件列表项总数
@@ -42,6 +63,36 @@ struct WidgetState {
     // 文件树模式
     file_tree_mode: u8,
     summary: DockerAnalyzeSummary,
+    // 内容相同的文件
+    duplicate_file_list: Vec<DuplicateFileGroup>,
+    // 添加后又删除的文件
+    ghost_file_list: Vec<GhostFileInfo>,
+    // 告警信息
+    warning_list: Vec<String>,
+    // diff id校验失败的layer
+    diff_id_mismatch_list: Vec<DiffIdMismatchInfo>,
+    // 基础镜像
+    base_image: Option<BaseImageInfo>,
+    // 包管理工具的缓存
+    package_cache_list: Vec<PackageCacheInfo>,
+    // 不符合检测规则的问题
+    lint_issue_list: Vec<LintIssue>,
+    // 敏感信息
+    secret_list: Vec<SecretFinding>,
+    // 语言生态的组件
+    component_list: Vec<LanguageComponent>,
+    // 匹配的漏洞
+    vulnerability_list: Vec<VulnerabilityFinding>,
+    // elf二进制文件
+    elf_binary_list: Vec<ElfBinaryInfo>,
+    // 按文件类型汇总的大小
+    file_type_list: Vec<FileTypeSize>,
+    // 权限与属主的问题
+    permission_finding_list: Vec<PermissionFinding>,
+    // 镜像的运行配置
+    image_config: ImageExtraInfo,
+    // 影响可重现构建的问题
+    reproducibility: ReproducibilityReport,
 }
 
 static LAYERS_WIDGET: &str = "layers";
@@ -110,6 +161,61 @@ impl WidgetState {
     fn change_file_tree_mode(&mut self, mode: u8) {
         self.file_tree_mode = mode;
     }
+    // 标记的layer范围（仅标记起始时为该layer）
+    fn get_range(&self) -> Option<(usize, usize)> {
+        let start = self.range_start?;
+        let end = self.range_end.unwrap_or(start);
+        Some((start.min(end), start.max(end)))
+    }
+    // 依次标记起始与结束layer，再次标记则清除
+    fn mark_layer(&mut self) {
+        if self.layer_file_list.len() != self.file_tree_list.len() {
+            return;
+        }
+        if self.range_start.is_none() {
+            self.range_start = Some(self.selected_layer);
+        } else if self.range_end.is_none() {
+            self.range_end = Some(self.selected_layer);
+            if let Some((start, end)) = self.get_range() {
+                // 起始layer之前的文件系统与结束layer的文件系统对比
+                let before = if start == 0 {
+                    vec![]
+                } else {
+                    build_merged_file_tree(&self.layer_file_list, start - 1)
+                };
+                let after = build_merged_file_tree(&self.layer_file_list, end);
+                self.range_file_tree = diff_file_tree(&before, &after);
+            }
+        } else {
+            self.clear_range();
+        }
+        if self.is_files_widget_active() {
+            self.select_file(0);
+        }
+    }
+    fn clear_range(&mut self) {
+        self.range_start = None;
+        self.range_end = None;
+        self.range_file_tree = vec![];
+    }
+    // 生成当前层叠加后的文件树，切换层时重新生成
+    fn update_merged_file_tree(&mut self) {
+        if self.merged_layer == Some(self.selected_layer) {
+            return;
+        }
+        self.merged_file_tree = build_merged_file_tree(&self.layer_file_list, self.selected_layer);
+        self.merged_layer = Some(self.selected_layer);
+    }
+    fn toggle_merged(&mut self) {
+        // 无叠加数据时不切换
+        if self.layer_file_list.len() != self.file_tree_list.len() {
+            return;
+        }
+        self.merged = !self.merged;
+        if self.is_files_widget_active() {
+            self.select_file(0);
+        }
+    }
 }
 
 pub fn run_app(result: DockerAnalyzeResult) -> Result<(), Box<dyn Error>> {
@@ -131,12 +237,28 @@ pub fn run_app(result: DockerAnalyzeResult) -> Result<(), Box<dyn Error>> {
         layers: result.layers,
         selected_layer: 0,
         file_tree_list: result.file_tree_list,
+        layer_file_list: result.layer_file_list,
         size: result.size,
         total_size: result.total_size,
         // 可以选中的widget列表顺序
         active_list: vec![LAYERS_WIDGET.to_string(), FILES_WIDGET.to_string()],
         active: LAYERS_WIDGET.to_string(),
         summary,
+        duplicate_file_list: result.duplicate_file_list,
+        ghost_file_list: result.ghost_file_list,
+        warning_list: result.warning_list,
+        diff_id_mismatch_list: result.diff_id_mismatch_list,
+        base_image: result.base_image,
+        package_cache_list: result.package_cache_list,
+        lint_issue_list: result.lint_issue_list,
+        secret_list: result.secret_list,
+        component_list: result.component_list,
+        vulnerability_list: result.vulnerability_list,
+        elf_binary_list: result.elf_binary_list,
+        file_type_list: result.file_type_list,
+        permission_finding_list: result.permission_finding_list,
+        image_config: result.image_config,
+        reproducibility: result.reproducibility,
         ..Default::default()
     };
     let (tx, rx) = sync_channel::<bool>(1);
@@ -204,7 +326,14 @@ pub fn run_app(result: DockerAnalyzeResult) -> Result<(), Box<dyn Error>> {
                 KeyCode::Char('0') => state.change_file_tree_mode(0),
                 KeyCode::Char('1') => state.change_file_tree_mode(1),
                 KeyCode::Char('2') => state.change_file_tree_mode(2),
-                KeyCode::Esc => state.change_file_tree_mode(0),
+                KeyCode::Esc => {
+                    state.change_file_tree_mode(0);
+                    state.clear_range();
+                }
+                // 切换当前层与叠加后的文件树
+                KeyCode::Char('a') => state.toggle_merged(),
+                // 标记layer范围，展示范围内的变化
+                KeyCode::Char('m') => state.mark_layer(),
 
                 _ => continue,
             }
@@ -232,6 +361,7 @@ fn draw_widgets(f: &mut Frame, state: &mut WidgetState) {
         layers::LayersWidgetOption {
             is_active: state.is_layers_widget_active(),
             selected_layer: state.selected_layer,
+            range: state.get_range(),
         },
     );
     let layer = state
@@ -263,19 +393,60 @@ fn draw_widgets(f: &mut Frame, state: &mut WidgetState) {
         total_size: state.total_size,
         size: state.size,
         summary: state.summary.clone(),
+        duplicate_file_list: state.duplicate_file_list.clone(),
+        ghost_file_list: state.ghost_file_list.clone(),
+        failed_layers: state
+            .layers
+            .iter()
+            .enumerate()
+            .filter(|(_, layer)| layer.error.is_some())
+            .map(|(index, _)| index)
+            .collect(),
+        warning_list: state.warning_list.clone(),
+        diff_id_mismatch_list: state.diff_id_mismatch_list.clone(),
+        base_image: state.base_image.clone(),
+        package_cache_list: state.package_cache_list.clone(),
+        lint_issue_list: state.lint_issue_list.clone(),
+        secret_list: state.secret_list.clone(),
+        component_list: state.component_list.clone(),
+        vulnerability_list: state.vulnerability_list.clone(),
+        elf_binary_list: state.elf_binary_list.clone(),
+        file_type_list: state.file_type_list.clone(),
+        permission_finding_list: state.permission_finding_list.clone(),
+        image_config: state.image_config.clone(),
+        reproducibility: state.reproducibility.clone(),
     });
     f.render_widget(layers_widget.widget, left_chunks[0]);
     f.render_widget(detail_widget.widget, left_chunks[1]);
     f.render_widget(image_detail_widget.widget, left_chunks[2]);
 
     // 文件列表
+    // 标记了范围时展示范围内的变化
+    let range = if state.range_end.is_some() {
+        state.get_range()
+    } else {
+        None
+    };
+    let mut selected_layer = state.selected_layer;
+    let file_tree_list = if range.is_some() {
+        selected_layer = 0;
+        std::slice::from_ref(&state.range_file_tree)
+    } else if state.merged {
+        selected_layer = 0;
+        state.update_merged_file_tree();
+        std::slice::from_ref(&state.merged_file_tree)
+    } else {
+        &state.file_tree_list
+    };
     let files_widget = files::new_files_widget(
-        &state.file_tree_list,
+        file_tree_list,
         files::FilesWidgetOption {
             is_active: state.is_files_widget_active(),
-            selected_layer: state.selected_layer,
+            selected_layer,
+            range,
             area: chunks[1],
             mode: state.file_tree_mode,
+            merged: state.merged,
         },
     );
     if state.file_count != files_widget.file_count {
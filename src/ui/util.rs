@@ -1,4 +1,5 @@
 use ratatui::{prelude::*, widgets::*};
+use std::borrow::Cow;
 
 use unicode_width::UnicodeWidthStr;
 
@@ -8,7 +9,7 @@ pub fn get_width(str: &str) -> u16 {
 }
 
 // 创建block
-pub fn create_block(title: &str) -> Block<'_> {
+pub fn create_block<'a, T: Into<Cow<'a, str>>>(title: T) -> Block<'a> {
     Block::default().borders(Borders::ALL).title(Span::styled(
         title,
         Style::default().add_modifier(Modifier::BOLD),
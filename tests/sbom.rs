@@ -0,0 +1,95 @@
+use sha2::{Digest, Sha256};
+use std::process::Command;
+
+fn add_file(builder: &mut tar::Builder<Vec<u8>>, path: &str, data: &[u8]) {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_uid(0);
+    header.set_gid(0);
+    header.set_mtime(1700000000);
+    header.set_cksum();
+    builder.append_data(&mut header, path, data).unwrap();
+}
+
+fn sha256(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+// 生成docker save格式的镜像，包含os-release与dpkg的数据库
+fn new_image_tar() -> Vec<u8> {
+    let mut layer = tar::Builder::new(vec![]);
+    add_file(
+        &mut layer,
+        "etc/os-release",
+        b"PRETTY_NAME=\"Debian GNU/Linux 12 (bookworm)\"\nID=debian\nVERSION_ID=\"12\"\n",
+    );
+    add_file(
+        &mut layer,
+        "var/lib/dpkg/status",
+        b"Package: libc6\nSource: glibc (2.36-9)\nStatus: install ok installed\nArchitecture: amd64\nVersion: 2.36-9+deb12u4\n",
+    );
+    let layer = layer.into_inner().unwrap();
+    let layer_digest = sha256(&layer);
+    let config = serde_json::json!({
+        "architecture": "amd64",
+        "os": "linux",
+        "config": {},
+        "rootfs": {
+            "type": "layers",
+            "diff_ids": [format!("sha256:{layer_digest}")],
+        },
+        "history": [
+            {
+                "created": "2024-01-01T00:00:00Z",
+                "created_by": "ADD rootfs.tar /",
+            }
+        ],
+    });
+    let config = serde_json::to_vec(&config).unwrap();
+    let config_name = format!("blobs/sha256/{}", sha256(&config));
+    let layer_name = format!("blobs/sha256/{layer_digest}");
+    let manifest = serde_json::json!([{
+        "Config": config_name,
+        "RepoTags": ["sbom:test"],
+        "Layers": [layer_name],
+    }]);
+
+    let mut image = tar::Builder::new(vec![]);
+    add_file(&mut image, &layer_name, &layer);
+    add_file(&mut image, &config_name, &config);
+    add_file(
+        &mut image,
+        "manifest.json",
+        &serde_json::to_vec(&manifest).unwrap(),
+    );
+    image.into_inner().unwrap()
+}
+
+#[test]
+fn test_sbom_stdout() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("image.tar");
+    std::fs::write(&file, new_image_tar()).unwrap();
+    // 使用独立的配置目录
+    let output = Command::new(env!("CARGO_BIN_EXE_diving"))
+        .env("HOME", dir.path())
+        .arg(format!("file://{}", file.to_string_lossy()))
+        .arg("--sbom")
+        .arg("cyclonedx")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    // stdout仅为sbom，日志输出至stderr
+    let document: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!("CycloneDX", document["bomFormat"]);
+    let components = document["components"].as_array().unwrap();
+    assert!(components
+        .iter()
+        .any(|item| item["name"] == "libc6" && item["version"] == "2.36-9+deb12u4"));
+    assert!(!output.stderr.is_empty());
+}
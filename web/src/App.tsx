@@ -15,6 +15,7 @@ import {
   Typography,
   Space,
   List,
+  Alert,
 } from "antd";
 import axios, { AxiosError } from "axios";
 import prettyBytes from "pretty-bytes";
@@ -42,18 +43,252 @@ interface ImageAnalyzeResult {
   size: number;
   totalSize: number;
   fileTreeList: FileTreeList[][];
+  mergedFileTree: FileTreeList[];
   fileSummaryList: FileSummaryList[];
   bigModifiedFileList: ModifiedFile[];
+  duplicateFileList: DuplicateFileGroup[];
+  ghostFileList: GhostFile[];
+  incomplete: boolean;
+  warningList: string[];
+  diffIdMismatchList: DiffIdMismatch[];
+  baseImage?: BaseImage;
+  packageCacheList: PackageCache[];
+  lintIssueList: LintIssue[];
+  secretList: SecretFinding[];
+  sbom?: ImageSbom;
+  componentList: LanguageComponent[];
+  vulnerabilityList: VulnerabilityFinding[];
+  elfBinaryList: ElfBinary[];
+  fileTypeList: FileTypeSize[];
+  permissionFindingList: PermissionFinding[];
+  imageConfig?: ImageConfig;
+  reproducibility?: ReproducibilityReport;
+}
+
+interface ReproducibleFile {
+  path: string;
+  layerIndex: number;
+  mtime: number;
+  uid: number;
+  gid: number;
+}
+
+interface TarHeaderAudit {
+  layerIndex: number;
+  unsortedCount: number;
+  formats: string[];
+  accessTimeCount: number;
+  ownerNameCount: number;
+}
+
+interface BuildIdInfo {
+  path: string;
+  layerIndex: number;
+  buildId: string;
+}
+
+interface ReproducibilityReport {
+  sourceDateEpoch?: number;
+  sourceDateEpochFrom: string;
+  mtimeFileList: ReproducibleFile[];
+  mtimeFileCount: number;
+  ownerFileList: ReproducibleFile[];
+  ownerFileCount: number;
+  tarHeaderList: TarHeaderAudit[];
+  buildIdList: BuildIdInfo[];
+}
+
+interface ImageHealthcheck {
+  Test: string[];
+  Interval?: number;
+  Timeout?: number;
+  StartPeriod?: number;
+  StartInterval?: number;
+  Retries?: number;
+}
+
+interface ImageConfig {
+  User?: string;
+  ExposedPorts?: Record<string, unknown>;
+  Env?: string[];
+  Entrypoint?: string[];
+  Cmd?: string[];
+  Volumes?: Record<string, unknown>;
+  WorkingDir?: string;
+  Labels?: Record<string, string>;
+  StopSignal?: string;
+  OnBuild?: string[];
+  Healthcheck?: ImageHealthcheck;
+  Shell?: string[];
+}
+
+interface PermissionFinding {
+  kind: string;
+  path: string;
+  mode: string;
+  uid: number;
+  gid: number;
+  layerIndex: number;
+}
+
+interface FileTypeSize {
+  fileType: string;
+  count: number;
+  size: number;
+}
+
+interface ElfBinary {
+  path: string;
+  size: number;
+  layerIndex: number;
+  linkage: string;
+  interpreter: string;
+  needed: string[];
+  runpath: string[];
+  debugSize: number;
+  symbolSize: number;
+}
+
+interface VulnerabilityFinding {
+  id: string;
+  aliases: string[];
+  summary: string;
+  severity: string;
+  ecosystem: string;
+  package: string;
+  version: string;
+  fixedVersion: string;
+  layerIndex: number;
+}
+
+interface LanguageComponent {
+  ecosystem: string;
+  name: string;
+  version: string;
+  path: string;
+  size: number;
+  layerIndex: number;
+}
+
+interface ImageSbom {
+  os?: {
+    id: string;
+    versionId: string;
+    prettyName: string;
+  };
+  packageList: SbomPackage[];
+}
+
+interface SbomPackage {
+  name: string;
+  source: string;
+  version: string;
+  arch: string;
+  ecosystem: string;
+  purl: string;
+  layerIndex: number;
+}
+
+interface SecretFinding {
+  layerIndex?: number;
+  path: string;
+  rule: string;
+  redacted: string;
+}
+
+interface LintIssue {
+  rule: string;
+  severity: string;
+  message: string;
+  paths: string[];
+  count: number;
+}
+
+interface PackageCache {
+  ecosystem: string;
+  path: string;
+  size: number;
+  fileCount: number;
+  layerIndex: number;
+}
+
+interface BaseImage {
+  name: string;
+  digest: string;
+  source: string;
+  layerCount: number;
+  size: number;
+  totalSize: number;
+  appSize: number;
+  appTotalSize: number;
+}
+
+interface DiffIdMismatch {
+  layerIndex: number;
+  expected: string;
+  actual: string;
+}
+
+interface GhostFile {
+  path: string;
+  size: number;
+  addedLayerIndex: number;
+  addedCmd: string;
+  removedLayerIndex: number;
+  removedCmd: string;
+}
+
+interface MergedFile {
+  layerIndex: number;
+  info: Info;
+}
+
+interface DuplicateFileGroup {
+  digest: string;
+  size: number;
+  wastedSize: number;
+  files: MergedFile[];
 }
 
 interface Layer {
   created: string;
   digest: string;
+  diffId: string;
+  diffIdVerified?: boolean;
+  diffIdUnverified?: string;
+  contentSkipped?: string;
   mediaType: string;
   cmd: string;
   size: number;
   unpackSize: number;
   empty: boolean;
+  toc?: LayerToc;
+  unsupported?: string;
+  error?: string;
+  base: boolean;
+  fileTypeList?: FileTypeSize[];
+  compression?: LayerCompression;
+}
+
+interface ZstdEstimate {
+  level: number;
+  size: number;
+}
+
+interface LayerCompression {
+  ratio: number;
+  uncompressed: boolean;
+  precompressed: boolean;
+  sampleSize: number;
+  zstdEstimateList: ZstdEstimate[];
+}
+
+interface LayerToc {
+  format: string;
+  digest: string;
+  size: number;
+  entryCount: number;
+  chunkCount: number;
 }
 
 interface FileTreeList {
@@ -65,6 +300,7 @@ interface FileTreeList {
   uid: number;
   gid: number;
   op: number;
+  layerIndex?: number;
   children: FileTreeList[];
 }
 
@@ -82,6 +318,7 @@ interface Info {
   uid: number;
   gid: number;
   isWhiteout: any;
+  digest?: string;
 }
 interface FileWastedSummary {
   path: string;
@@ -239,15 +476,48 @@ const getImageSummary = (result: ImageAnalyzeResult) => {
   const otherLayerSize = result.totalSize - firstNotEmptyLayerSize;
 
   const score = (100 - (wastedSize * 100) / result.totalSize).toFixed(2);
+  let duplicateSize = 0;
+  (result.duplicateFileList || []).forEach((item) => {
+    duplicateSize += item.wastedSize;
+  });
+  let stripSavings = 0;
+  (result.elfBinaryList || []).forEach((item) => {
+    stripSavings += item.debugSize + item.symbolSize;
+  });
+  // 按压缩级别汇总以zstd压缩layer后可节省的空间
+  const zstdSavings = new Map<number, number>();
+  result.layers.forEach((layer) => {
+    (layer.compression?.zstdEstimateList || []).forEach((item) => {
+      const savings = zstdSavings.get(item.level) || 0;
+      zstdSavings.set(item.level, savings + layer.size - item.size);
+    });
+  });
+  const zstdSavingsDesc = Array.from(zstdSavings.entries())
+    .sort((a, b) => a[0] - b[0])
+    .map(([level, savings]) => {
+      const sign = savings < 0 ? "-" : "";
+      return `-${level} ${sign}${prettyBytes(Math.abs(savings))}`;
+    })
+    .join(", ");
 
   const imageDescriptions = {
     score: `${score}%`,
     size: `${prettyBytes(result.totalSize)} / ${prettyBytes(result.size)}`,
     otherSize: prettyBytes(otherLayerSize),
     wastedSize: prettyBytes(wastedSize),
+    duplicateSize: prettyBytes(duplicateSize),
+    stripSavings: prettyBytes(stripSavings),
+    zstdSavings: zstdSavingsDesc,
     osArch: `${result.os}/${result.arch}`,
     created: result.layers[result.layers.length - 1].created,
+    baseImage: "",
+    appSize: "",
   };
+  const { baseImage } = result;
+  if (baseImage) {
+    imageDescriptions.baseImage = `${baseImage.name} (${baseImage.layerCount} layers, ${prettyBytes(baseImage.totalSize)} / ${prettyBytes(baseImage.size)})`;
+    imageDescriptions.appSize = `${prettyBytes(baseImage.appTotalSize)} / ${prettyBytes(baseImage.appSize)}`;
+  }
   return {
     wastedList,
     imageDescriptions,
@@ -275,9 +545,11 @@ interface FileTreeViewOption {
 
 const opRemoved = 1;
 const opModified = 2;
+const opAdded = 3;
 
 const isModifiedRemoved = (item: FileTreeList) => {
-  const arr = [opRemoved, opModified];
+  // 叠加视图中当前层修改的文件为added
+  const arr = [opRemoved, opModified, opAdded];
   if (arr.includes(item.op)) {
     return true;
   }
@@ -306,7 +578,8 @@ const isMatchKeyword = (item: FileTreeList, keyword: string) => {
 
 const addToFileTreeView = (
   onToggleExpand: (key: string) => void,
-  layer: Layer,
+  layers: Layer[],
+  currentLayer: number,
   list: ReactElement[],
   items: FileTreeList[],
   isLastList: boolean[],
@@ -352,6 +625,13 @@ const addToFileTreeView = (
     if (item.link) {
       name = `${name} → ${item.link}`;
     }
+    // 叠加视图中展示文件所属的层
+    const isMerged = item.layerIndex !== undefined && item.layerIndex !== null;
+    if (isMerged && item.children.length === 0) {
+      name = `${name} [L${(item.layerIndex as number) + 1}]`;
+    }
+    // 文件从其所属的层下载
+    const layer = layers[isMerged ? (item.layerIndex as number) : currentLayer];
     const padding = isLastList.length * 30;
 
     let className = "";
@@ -359,6 +639,8 @@ const addToFileTreeView = (
       className = "removed";
     } else if (item.op === opModified) {
       className = "modified";
+    } else if (item.op === opAdded) {
+      className = "added";
     }
     let icon: ReactElement = <></>;
     if (item.children.length) {
@@ -415,7 +697,8 @@ const addToFileTreeView = (
       tmp.push(isLast);
       const childAppendCount = addToFileTreeView(
         onToggleExpand,
-        layer,
+        layers,
+        currentLayer,
         list,
         item.children,
         tmp,
@@ -438,8 +721,13 @@ interface ImageDescriptions {
   size: string;
   otherSize: string;
   wastedSize: string;
+  duplicateSize: string;
+  stripSavings: string;
+  zstdSavings: string;
   osArch: string;
   created: string;
+  baseImage: string;
+  appSize: string;
 }
 interface AppState {
   version: string;
@@ -449,12 +737,32 @@ interface AppState {
   layers: Layer[];
   currentLayer: number;
   fileTreeList: FileTreeList[][];
+  // 叠加后的文件树（仅当前展示的层）
+  mergedFileTree: FileTreeList[];
+  mergedLayer: number;
+  merged: boolean;
+  // 分析时使用的镜像参数
+  analyzedImage: string;
   fileTreeViewOption: FileTreeViewOption;
   wastedList: FileWastedSummary[];
   imageName: string;
   arch: string;
   latestAnalyzeImages: string[];
   bigModifiedFileList: ModifiedFile[];
+  duplicateFileList: DuplicateFileGroup[];
+  ghostFileList: GhostFile[];
+  packageCacheList: PackageCache[];
+  lintIssueList: LintIssue[];
+  secretList: SecretFinding[];
+  sbom?: ImageSbom;
+  componentList: LanguageComponent[];
+  vulnerabilityList: VulnerabilityFinding[];
+  elfBinaryList: ElfBinary[];
+  fileTypeList: FileTypeSize[];
+  permissionFindingList: PermissionFinding[];
+  imageConfig: ImageConfig;
+  reproducibility?: ReproducibilityReport;
+  warningList: string[];
 }
 
 interface LatestImages {
@@ -488,12 +796,28 @@ class App extends Component {
       layers: [],
       currentLayer: 0,
       fileTreeList: [],
+      mergedFileTree: [],
+      mergedLayer: -1,
+      merged: false,
+      analyzedImage: "",
       fileTreeViewOption: {} as FileTreeViewOption,
       wastedList: [],
       imageName: image,
       arch,
       latestAnalyzeImages: [],
       bigModifiedFileList: [],
+      duplicateFileList: [],
+      ghostFileList: [],
+      packageCacheList: [],
+      lintIssueList: [],
+      secretList: [],
+      componentList: [],
+      vulnerabilityList: [],
+      elfBinaryList: [],
+      fileTypeList: [],
+      permissionFindingList: [],
+      imageConfig: {},
+      warningList: [],
       version: "",
     };
   }
@@ -525,10 +849,11 @@ class App extends Component {
       loading: true,
     });
     try {
-      let url = `/analyze?image=${image}`;
+      let analyzedImage = image;
       if (!/^(file|docker):\/\//.test(image) && arch) {
-        url += `?arch=${arch}`;
+        analyzedImage += `?arch=${arch}`;
       }
+      const url = `/analyze?image=${analyzedImage}`;
       const { data } = await request.get<ImageAnalyzeResult>(url, {
         timeout: 10 * 60 * 1000,
       });
@@ -542,10 +867,27 @@ class App extends Component {
         imageDescriptions: result.imageDescriptions,
         wastedList: result.wastedList,
         fileTreeList: data.fileTreeList,
+        mergedFileTree: [],
+        mergedLayer: -1,
+        analyzedImage,
         layers: data.layers,
         currentLayer: 0,
         gotResult: true,
         bigModifiedFileList: data.bigModifiedFileList,
+        duplicateFileList: data.duplicateFileList || [],
+        ghostFileList: data.ghostFileList || [],
+        packageCacheList: data.packageCacheList || [],
+        lintIssueList: data.lintIssueList || [],
+        secretList: data.secretList || [],
+        sbom: data.sbom,
+        componentList: data.componentList || [],
+        vulnerabilityList: data.vulnerabilityList || [],
+        elfBinaryList: data.elfBinaryList || [],
+        fileTypeList: data.fileTypeList || [],
+        permissionFindingList: data.permissionFindingList || [],
+        imageConfig: data.imageConfig || {},
+        reproducibility: data.reproducibility,
+        warningList: data.warningList || [],
       });
     } catch (err: any) {
       let msg = err?.message as string;
@@ -563,6 +905,28 @@ class App extends Component {
       });
     }
   }
+  // 获取叠加至该层后的文件树
+  async loadMergedFileTree(layer: number) {
+    const { analyzedImage, mergedLayer } = this.state;
+    if (mergedLayer === layer) {
+      return;
+    }
+    try {
+      const { data } = await request.get<FileTreeList[]>(
+        `/merged-file-tree?image=${analyzedImage}&layer=${layer}`,
+        {
+          timeout: 10 * 60 * 1000,
+        },
+      );
+      addKeyToFileTreeItem(data, "");
+      this.setState({
+        mergedFileTree: data,
+        mergedLayer: layer,
+      });
+    } catch (err: any) {
+      message.error(err?.message || "get merged file tree fail", 10);
+    }
+  }
   render(): ReactNode {
     const {
       imageName,
@@ -572,11 +936,28 @@ class App extends Component {
       layers,
       currentLayer,
       fileTreeList,
+      mergedFileTree,
+      mergedLayer,
+      merged,
       fileTreeViewOption,
       wastedList,
       arch,
       latestAnalyzeImages,
       bigModifiedFileList,
+      duplicateFileList,
+      ghostFileList,
+      packageCacheList,
+      lintIssueList,
+      secretList,
+      sbom,
+      componentList,
+      vulnerabilityList,
+      elfBinaryList,
+      fileTypeList,
+      permissionFindingList,
+      imageConfig,
+      reproducibility,
+      warningList,
       version,
     } = this.state;
     const onToggleExpand = (key: string) => {
@@ -598,6 +979,9 @@ class App extends Component {
       this.setState({
         currentLayer: index,
       });
+      if (merged) {
+        this.loadMergedFileTree(index);
+      }
     };
 
     const getImageSummaryView = () => {
@@ -615,15 +999,102 @@ class App extends Component {
           <Descriptions.Item label={i18nGet("wastedSizeLabel")}>
             {imageDescriptions["wastedSize"]}
           </Descriptions.Item>
+          <Descriptions.Item label={i18nGet("duplicateSizeLabel")}>
+            {imageDescriptions["duplicateSize"]}
+          </Descriptions.Item>
+          <Descriptions.Item label={i18nGet("stripSavingsLabel")}>
+            {imageDescriptions["stripSavings"]}
+          </Descriptions.Item>
+          {imageDescriptions["zstdSavings"] && (
+            <Descriptions.Item label={i18nGet("zstdSavingsLabel")}>
+              {imageDescriptions["zstdSavings"]}
+            </Descriptions.Item>
+          )}
           <Descriptions.Item label={i18nGet("osArchLabel")}>
             {imageDescriptions["osArch"]}
           </Descriptions.Item>
           <Descriptions.Item label={i18nGet("createdLabel")}>
             {new Date(imageDescriptions["created"]).toLocaleString()}
           </Descriptions.Item>
+          {imageDescriptions["baseImage"] && (
+            <Descriptions.Item label={i18nGet("baseImageLabel")}>
+              {imageDescriptions["baseImage"]}
+            </Descriptions.Item>
+          )}
+          {imageDescriptions["appSize"] && (
+            <Descriptions.Item label={i18nGet("appSizeLabel")}>
+              {imageDescriptions["appSize"]}
+            </Descriptions.Item>
+          )}
+        </Descriptions>
+      );
+      return (
+        <div className="imageSummary mtop30">
+          {imageSummary}
+          {getImageConfigView()}
+        </div>
+      );
+    };
+
+    const getImageConfigView = () => {
+      // 纳秒转换为秒
+      const formatDuration = (value?: number) => {
+        if (!value) {
+          return "";
+        }
+        return `${value / 1e9}s`;
+      };
+      let healthcheck = "";
+      if (imageConfig.Healthcheck) {
+        const { Test, Interval, Timeout, StartPeriod, Retries } =
+          imageConfig.Healthcheck;
+        const options = [
+          ["interval", formatDuration(Interval)],
+          ["timeout", formatDuration(Timeout)],
+          ["start period", formatDuration(StartPeriod)],
+          ["retries", Retries ? Retries.toString() : ""],
+        ]
+          .filter((item) => item[1])
+          .map((item) => `${item[0]}: ${item[1]}`);
+        healthcheck = Test.join(" ");
+        if (options.length !== 0) {
+          healthcheck += ` (${options.join(", ")})`;
+        }
+      }
+      const items = [
+        ["Entrypoint", (imageConfig.Entrypoint || []).join(" ")],
+        ["Cmd", (imageConfig.Cmd || []).join(" ")],
+        [i18nGet("userLabel"), imageConfig.User || ""],
+        [i18nGet("workingDirLabel"), imageConfig.WorkingDir || ""],
+        [
+          i18nGet("exposedPortsLabel"),
+          Object.keys(imageConfig.ExposedPorts || {}).join(", "),
+        ],
+        [
+          i18nGet("volumesLabel"),
+          Object.keys(imageConfig.Volumes || {}).join(", "),
+        ],
+        ["Healthcheck", healthcheck],
+        ["StopSignal", imageConfig.StopSignal || ""],
+        ["OnBuild", (imageConfig.OnBuild || []).join("; ")],
+        ["Shell", (imageConfig.Shell || []).join(" ")],
+      ].filter((item) => item[1]);
+      if (items.length === 0) {
+        return <></>;
+      }
+      return (
+        <Descriptions
+          className="mtop30"
+          title={i18nGet("imageConfigTitle")}
+          column={2}
+        >
+          {items.map((item) => (
+            <Descriptions.Item key={item[0]} label={item[0]}>
+              {item[1]}
+            </Descriptions.Item>
+          ))}
         </Descriptions>
       );
-      return <div className="imageSummary mtop30">{imageSummary}</div>;
     };
 
     const layerOptions = layers.map((item, index) => {
@@ -641,6 +1112,21 @@ class App extends Component {
       }
 
       let label = `${index + 1}: ${digest.toUpperCase()}${sizeDesc}`;
+      if (item.unsupported) {
+        label += ` [${item.unsupported}]`;
+      }
+      if (item.base) {
+        label += " [base]";
+      }
+      if (item.error) {
+        label += " [failed]";
+      }
+      if (item.diffIdVerified === false) {
+        label += " [diff id mismatch]";
+      }
+      if (item.diffIdUnverified) {
+        label += " [unverified]";
+      }
       return {
         value: index,
         label,
@@ -667,11 +1153,16 @@ class App extends Component {
     });
 
     const fileTreeViewList = [] as ReactElement[];
+    const currentFileTree =
+      merged && mergedLayer === currentLayer
+        ? mergedFileTree
+        : fileTreeList[currentLayer];
     addToFileTreeView(
       onToggleExpand,
-      layers[currentLayer],
+      layers,
+      currentLayer,
       fileTreeViewList,
-      fileTreeList[currentLayer],
+      currentFileTree,
       [],
       fileTreeViewOption,
     );
@@ -735,7 +1226,23 @@ class App extends Component {
             </Checkbox>
           </Form.Item>
         </Col>
-        <Col span={8}>
+        <Col span={3}>
+          <Form.Item>
+            <Checkbox
+              onChange={(e) => {
+                this.setState({
+                  merged: e.target.checked,
+                });
+                if (e.target.checked) {
+                  this.loadMergedFileTree(currentLayer);
+                }
+              }}
+            >
+              {i18nGet("aggregatedLabel")}
+            </Checkbox>
+          </Form.Item>
+        </Col>
+        <Col span={5}>
           <Form.Item>
             <Input
               addonBefore={i18nGet("keywordsLabel")}
@@ -769,6 +1276,42 @@ class App extends Component {
                 <span className="bold">{i18nGet("createdLabel")}: </span>
                 {new Date(layerInfo.created).toLocaleString()}
               </span>
+              {layerInfo.error && (
+                <span className="error">
+                  <span className="bold">{i18nGet("errorLabel")}: </span>
+                  {layerInfo.error}
+                </span>
+              )}
+              {layerInfo.fileTypeList && layerInfo.fileTypeList.length !== 0 && (
+                <span>
+                  <span className="bold">{i18nGet("fileTypeLabel")}: </span>
+                  {layerInfo.fileTypeList
+                    .slice(0, 5)
+                    .map((item) => `${item.fileType} ${prettyBytes(item.size)}`)
+                    .join(", ")}
+                </span>
+              )}
+              {layerInfo.compression && (
+                <span>
+                  <span className="bold">{i18nGet("compressionLabel")}: </span>
+                  {[
+                    `${(layerInfo.compression.ratio * 100).toFixed(1)}%`,
+                    layerInfo.compression.uncompressed ? "uncompressed" : "",
+                    layerInfo.compression.precompressed ? "precompressed" : "",
+                  ]
+                    .filter((item) => item)
+                    .join(" ")}
+                  {layerInfo.compression.zstdEstimateList
+                    .map((item) => `, zstd -${item.level} ${prettyBytes(item.size)}`)
+                    .join("")}
+                </span>
+              )}
+              {layerInfo.toc && (
+                <span>
+                  <span className="bold">{i18nGet("tocLabel")}: </span>
+                  {`${layerInfo.toc.format}, ${layerInfo.toc.entryCount} entries, ${layerInfo.toc.chunkCount} chunks (${prettyBytes(layerInfo.toc.size)})`}
+                </span>
+              )}
               <span>
                 <span className="bold">{i18nGet("commandLabel")}: </span>
                 {layerInfo.cmd}
@@ -830,6 +1373,46 @@ class App extends Component {
       );
     };
 
+    const getFileTypeView = () => {
+      if (fileTypeList.length === 0) {
+        return <></>;
+      }
+      let total = 0;
+      fileTypeList.forEach((item) => {
+        total += item.size;
+      });
+      const list = fileTypeList.map((item) => {
+        const percent = total === 0 ? 0 : Math.floor((item.size * 100) / total);
+        return (
+          <li key={item.fileType}>
+            <span>{prettyBytes(item.size)}</span>
+            <span>{item.count}</span>
+            <span>
+              {item.fileType} ({percent}%)
+            </span>
+          </li>
+        );
+      });
+      let className = "wastedList";
+      if (isDarkMode()) {
+        className += " dark";
+      }
+      return (
+        <div className="mtop30">
+          <Card title={i18nGet("fileTypeTitle")}>
+            <ul className={className}>
+              <li>
+                <span>{i18nGet("totalSizeLabel")}</span>
+                <span>{i18nGet("countLabel")}</span>
+                <span>{i18nGet("fileTypeLabel")}</span>
+              </li>
+              {list}
+            </ul>
+          </Card>
+        </div>
+      );
+    };
+
     const getBigModifiedFileView = () => {
       if (bigModifiedFileList.length === 0) {
         return <></>;
@@ -870,6 +1453,479 @@ class App extends Component {
         </div>
       );
     };
+    const getIncompleteView = () => {
+      const failedLayers = layers
+        .map((item, index) => (item.error ? `${index + 1}` : ""))
+        .filter((item) => item);
+      if (failedLayers.length === 0) {
+        return <></>;
+      }
+      return (
+        <Alert
+          className="mtop30"
+          type="error"
+          showIcon
+          message={`${i18nGet("incompleteTips")}${failedLayers.join(", ")}`}
+        />
+      );
+    };
+    const getIntegrityView = () => {
+      const arr = layers
+        .map((item, index) => (item.diffIdVerified === false ? `${index + 1}` : ""))
+        .filter((item) => item);
+      const unverifiedLayers = layers
+        .map((item, index) => (item.diffIdUnverified ? `${index + 1}` : ""))
+        .filter((item) => item);
+      const contentSkippedLayers = layers
+        .map((item, index) => (item.contentSkipped ? `${index + 1}` : ""))
+        .filter((item) => item);
+      return (
+        <>
+          {arr.length !== 0 && (
+            <Alert
+              className="mtop30"
+              type="error"
+              showIcon
+              message={`${i18nGet("diffIdMismatchTips")}${arr.join(", ")}`}
+            />
+          )}
+          {unverifiedLayers.length !== 0 && (
+            <Alert
+              className="mtop30"
+              type="warning"
+              showIcon
+              message={`${i18nGet("diffIdUnverifiedTips")}${unverifiedLayers.join(", ")}`}
+            />
+          )}
+          {contentSkippedLayers.length !== 0 && (
+            <Alert
+              className="mtop30"
+              type="warning"
+              showIcon
+              message={`${i18nGet("contentSkippedTips")}${contentSkippedLayers.join(", ")}`}
+            />
+          )}
+        </>
+      );
+    };
+    const getWarningView = () => {
+      return warningList.map((item) => (
+        <Alert key={item} className="mtop30" type="warning" showIcon message={item} />
+      ));
+    };
+    const getLintView = () => {
+      return lintIssueList.map((item) => {
+        let message = `${i18nGet("lintTips")}${item.rule}, ${item.message}`;
+        if (item.paths.length !== 0) {
+          message += ` (${item.count}): ${item.paths.join(", ")}`;
+        }
+        return (
+          <Alert
+            key={item.rule}
+            className="mtop30"
+            type={item.severity === "warning" ? "warning" : "error"}
+            showIcon
+            message={message}
+          />
+        );
+      });
+    };
+    const getSecretView = () => {
+      return secretList.map((item, index) => {
+        let location = item.path;
+        if (item.layerIndex !== undefined && item.layerIndex !== null) {
+          location = `L${item.layerIndex + 1} ${item.path}`;
+        }
+        return (
+          <Alert
+            key={`${index}-${item.path}`}
+            className="mtop30"
+            type="error"
+            showIcon
+            message={`${i18nGet("secretTips")}${location}, ${item.rule}: ${item.redacted}`}
+          />
+        );
+      });
+    };
+    const getDuplicateFileView = () => {
+      if (duplicateFileList.length === 0) {
+        return <></>;
+      }
+      const list = duplicateFileList.map((item) => {
+        const paths = item.files.map((file) => `/${file.info.path}`);
+        return (
+          <li key={item.digest}>
+            <span>{prettyBytes(item.wastedSize)}</span>
+            <span>{item.files.length}</span>
+            <span>{paths.join(", ")}</span>
+          </li>
+        );
+      });
+      let className = "wastedList";
+      if (isDarkMode()) {
+        className += " dark";
+      }
+      return (
+        <div className="mtop30">
+          <Card title={i18nGet("duplicateFileTitle")}>
+            <ul className={className}>
+              <li>
+                <span>{i18nGet("wastedSizeLabel")}</span>
+                <span>{i18nGet("countLabel")}</span>
+                <span>{i18nGet("pathLabel")}</span>
+              </li>
+              {list}
+            </ul>
+          </Card>
+        </div>
+      );
+    };
+    const getGhostFileView = () => {
+      if (ghostFileList.length === 0) {
+        return <></>;
+      }
+      const list = ghostFileList.map((item) => {
+        const title = `${item.addedLayerIndex + 1}: ${item.addedCmd}\n${
+          item.removedLayerIndex + 1
+        }: ${item.removedCmd}`;
+        return (
+          <li key={`${item.removedLayerIndex}-${item.path}`} title={title}>
+            <span>{prettyBytes(item.size)}</span>
+            <span>
+              {item.addedLayerIndex + 1} → {item.removedLayerIndex + 1}
+            </span>
+            <span>/{item.path}</span>
+          </li>
+        );
+      });
+      let className = "wastedList";
+      if (isDarkMode()) {
+        className += " dark";
+      }
+      return (
+        <div className="mtop30">
+          <Card title={i18nGet("ghostFileTitle")}>
+            <ul className={className}>
+              <li>
+                <span>{i18nGet("sizeLabel")}</span>
+                <span>{i18nGet("layerLabel")}</span>
+                <span>{i18nGet("pathLabel")}</span>
+              </li>
+              {list}
+            </ul>
+          </Card>
+        </div>
+      );
+    };
+    const getPackageCacheView = () => {
+      if (packageCacheList.length === 0) {
+        return <></>;
+      }
+      let totalSize = 0;
+      const list = packageCacheList.map((item) => {
+        totalSize += item.size;
+        const title = `${item.layerIndex + 1}: ${layers[item.layerIndex]?.cmd || ""}`;
+        return (
+          <li key={item.path} title={title}>
+            <span>{prettyBytes(item.size)}</span>
+            <span>{item.layerIndex + 1}</span>
+            <span>
+              {item.path} ({item.ecosystem})
+            </span>
+          </li>
+        );
+      });
+      let className = "wastedList";
+      if (isDarkMode()) {
+        className += " dark";
+      }
+      return (
+        <div className="mtop30">
+          <Card
+            title={`${i18nGet("packageCacheTitle")} (${prettyBytes(totalSize)})`}
+          >
+            <ul className={className}>
+              <li>
+                <span>{i18nGet("sizeLabel")}</span>
+                <span>{i18nGet("layerLabel")}</span>
+                <span>{i18nGet("pathLabel")}</span>
+              </li>
+              {list}
+            </ul>
+          </Card>
+        </div>
+      );
+    };
+    const getUnstrippedBinaryView = () => {
+      const list = elfBinaryList
+        .filter((item) => item.debugSize + item.symbolSize > 0)
+        .map((item) => {
+          let title = `${item.layerIndex + 1}: ${layers[item.layerIndex]?.cmd || ""}`;
+          if (item.interpreter) {
+            title += `\ninterpreter: ${item.interpreter}`;
+          }
+          if (item.needed.length !== 0) {
+            title += `\nneeded: ${item.needed.join(", ")}`;
+          }
+          return (
+            <li key={item.path} title={title}>
+              <span>{prettyBytes(item.debugSize + item.symbolSize)}</span>
+              <span>{item.layerIndex + 1}</span>
+              <span>
+                {item.path} ({item.linkage}, {prettyBytes(item.size)})
+              </span>
+            </li>
+          );
+        });
+      if (list.length === 0) {
+        return <></>;
+      }
+      let className = "wastedList";
+      if (isDarkMode()) {
+        className += " dark";
+      }
+      return (
+        <div className="mtop30">
+          <Card title={i18nGet("unstrippedBinaryTitle")}>
+            <ul className={className}>
+              <li>
+                <span>{i18nGet("savingsLabel")}</span>
+                <span>{i18nGet("layerLabel")}</span>
+                <span>{i18nGet("pathLabel")}</span>
+              </li>
+              {list}
+            </ul>
+          </Card>
+        </div>
+      );
+    };
+    const getPermissionView = () => {
+      if (permissionFindingList.length === 0) {
+        return <></>;
+      }
+      const list = permissionFindingList.map((item) => {
+        return (
+          <li
+            key={`${item.kind}-${item.path}`}
+            title={`${item.layerIndex + 1}: ${layers[item.layerIndex]?.cmd || ""}`}
+          >
+            <span>{item.mode}</span>
+            <span>{item.layerIndex + 1}</span>
+            <span>
+              {item.path} ({item.kind}, {item.uid}:{item.gid})
+            </span>
+          </li>
+        );
+      });
+      let className = "wastedList";
+      if (isDarkMode()) {
+        className += " dark";
+      }
+      return (
+        <div className="mtop30">
+          <Card title={i18nGet("permissionTitle")}>
+            <ul className={className}>
+              <li>
+                <span>{i18nGet("permissionLabel")}</span>
+                <span>{i18nGet("layerLabel")}</span>
+                <span>{i18nGet("pathLabel")}</span>
+              </li>
+              {list}
+            </ul>
+          </Card>
+        </div>
+      );
+    };
+    const getReproducibilityView = () => {
+      if (!reproducibility) {
+        return <></>;
+      }
+      const rows: [number, string, string][] = [];
+      reproducibility.mtimeFileList.forEach((item) => {
+        rows.push([item.layerIndex, "mtime", `${item.path} (${item.mtime})`]);
+      });
+      reproducibility.ownerFileList.forEach((item) => {
+        rows.push([
+          item.layerIndex,
+          "owner",
+          `${item.path} (${item.uid}:${item.gid})`,
+        ]);
+      });
+      reproducibility.tarHeaderList.forEach((item) => {
+        rows.push([
+          item.layerIndex,
+          "tar header",
+          `${item.unsortedCount} unsorted, formats ${item.formats.join("/")}, ${item.accessTimeCount} with atime/ctime, ${item.ownerNameCount} with user/group name`,
+        ]);
+      });
+      reproducibility.buildIdList.forEach((item) => {
+        rows.push([
+          item.layerIndex,
+          "build id",
+          `${item.path} (${item.buildId})`,
+        ]);
+      });
+      if (rows.length === 0) {
+        return <></>;
+      }
+      const list = rows.map(([layerIndex, kind, desc], index) => {
+        return (
+          <li
+            key={`${index}-${kind}`}
+            title={`${layerIndex + 1}: ${layers[layerIndex]?.cmd || ""}`}
+          >
+            <span>{kind}</span>
+            <span>{layerIndex + 1}</span>
+            <span>{desc}</span>
+          </li>
+        );
+      });
+      let title = i18nGet("reproducibilityTitle");
+      if (reproducibility.sourceDateEpoch !== undefined) {
+        title += ` (SOURCE_DATE_EPOCH: ${reproducibility.sourceDateEpoch}, ${reproducibility.sourceDateEpochFrom})`;
+      }
+      let className = "wastedList";
+      if (isDarkMode()) {
+        className += " dark";
+      }
+      return (
+        <div className="mtop30">
+          <Card title={title}>
+            <ul className={className}>
+              <li>
+                <span>{i18nGet("kindLabel")}</span>
+                <span>{i18nGet("layerLabel")}</span>
+                <span>{i18nGet("pathLabel")}</span>
+              </li>
+              {list}
+            </ul>
+          </Card>
+        </div>
+      );
+    };
+    const getVulnerabilityView = () => {
+      if (vulnerabilityList.length === 0) {
+        return <></>;
+      }
+      const list = vulnerabilityList.map((item, index) => {
+        const title = `${item.layerIndex + 1}: ${layers[item.layerIndex]?.cmd || ""}`;
+        let desc = `${item.id} ${item.package}@${item.version}`;
+        if (item.fixedVersion) {
+          desc += ` → ${item.fixedVersion}`;
+        }
+        if (item.summary) {
+          desc += ` (${item.summary})`;
+        }
+        return (
+          <li key={`${index}-${item.id}`} title={title}>
+            <span>{item.severity || "-"}</span>
+            <span>{item.layerIndex + 1}</span>
+            <span>{desc}</span>
+          </li>
+        );
+      });
+      let className = "wastedList";
+      if (isDarkMode()) {
+        className += " dark";
+      }
+      return (
+        <div className="mtop30">
+          <Card title={`${i18nGet("vulnerabilityTitle")} (${vulnerabilityList.length})`}>
+            <ul className={className}>
+              <li>
+                <span>{i18nGet("severityLabel")}</span>
+                <span>{i18nGet("layerLabel")}</span>
+                <span>{i18nGet("vulnerabilityLabel")}</span>
+              </li>
+              {list}
+            </ul>
+          </Card>
+        </div>
+      );
+    };
+    const getComponentView = () => {
+      const list = componentList
+        .filter((item) => item.size > 0)
+        .map((item, index) => {
+          const title = `${item.layerIndex + 1}: ${layers[item.layerIndex]?.cmd || ""}`;
+          return (
+            <li key={`${index}-${item.path}`} title={title}>
+              <span>{prettyBytes(item.size)}</span>
+              <span>{item.layerIndex + 1}</span>
+              <span>
+                {item.name}@{item.version} ({item.ecosystem}) {item.path}
+              </span>
+            </li>
+          );
+        });
+      if (list.length === 0) {
+        return <></>;
+      }
+      let className = "wastedList";
+      if (isDarkMode()) {
+        className += " dark";
+      }
+      return (
+        <div className="mtop30">
+          <Card title={`${i18nGet("componentTitle")} (${componentList.length})`}>
+            <ul className={className}>
+              <li>
+                <span>{i18nGet("sizeLabel")}</span>
+                <span>{i18nGet("layerLabel")}</span>
+                <span>{i18nGet("componentLabel")}</span>
+              </li>
+              {list}
+            </ul>
+          </Card>
+        </div>
+      );
+    };
+    const getSbomView = () => {
+      if (!sbom || sbom.packageList.length === 0) {
+        return <></>;
+      }
+      const list = sbom.packageList.map((item) => {
+        const title = `${item.layerIndex + 1}: ${layers[item.layerIndex]?.cmd || ""}`;
+        return (
+          <li key={item.purl} title={title}>
+            <span>{item.layerIndex + 1}</span>
+            <span>{item.ecosystem}</span>
+            <span>
+              {item.name} {item.version} ({item.arch})
+            </span>
+          </li>
+        );
+      });
+      let className = "wastedList";
+      if (isDarkMode()) {
+        className += " dark";
+      }
+      let title = `${i18nGet("sbomTitle")} (${sbom.packageList.length})`;
+      if (sbom.os?.prettyName) {
+        title += ` ${sbom.os.prettyName}`;
+      }
+      const downloadUrl = `./api/sbom?image=${encodeURIComponent(imageName)}&format=`;
+      const extra = (
+        <Space>
+          <a href={`${downloadUrl}cyclonedx`}>CycloneDX</a>
+          <a href={`${downloadUrl}spdx`}>SPDX</a>
+        </Space>
+      );
+      return (
+        <div className="mtop30">
+          <Card title={title} extra={extra}>
+            <ul className={className}>
+              <li>
+                <span>{i18nGet("layerLabel")}</span>
+                <span>{i18nGet("typeLabel")}</span>
+                <span>{i18nGet("packageLabel")}</span>
+              </li>
+              {list}
+            </ul>
+          </Card>
+        </div>
+      );
+    };
     const getSearchView = () => {
       const size = "large";
       const selectBefore = (
@@ -994,10 +2050,25 @@ class App extends Component {
           {gotResult && (
             <Content>
               <div className="contentWrapper">
+                {getIncompleteView()}
+                {getIntegrityView()}
+                {getLintView()}
+                {getSecretView()}
+                {getWarningView()}
                 {getImageSummaryView()}
                 {getLayerContentView()}
                 {getWastedSummaryView()}
+                {getFileTypeView()}
                 {getBigModifiedFileView()}
+                {getDuplicateFileView()}
+                {getGhostFileView()}
+                {getPackageCacheView()}
+                {getUnstrippedBinaryView()}
+                {getPermissionView()}
+                {getReproducibilityView()}
+                {getVulnerabilityView()}
+                {getSbomView()}
+                {getComponentView()}
               </div>
             </Content>
           )}
@@ -20,6 +20,7 @@ export default {
   layerLabel: "Layer",
   modificationLabel: "Modifications",
   expandLabel: "Expand",
+  aggregatedLabel: "Aggregated",
   keywordsLabel: "Keywords",
   wastedSummaryTitle: "Wasted Summary",
   totalSizeLabel: "Total Size",
@@ -27,4 +28,42 @@ export default {
   pathLabel: "Path",
   latestAnalyzeImagesTitle: "Latest Analyze Images",
   modifiedAddedLargeFileTitle: "Modified Or Add Large File",
+  duplicateFileTitle: "Duplicate Files",
+  duplicateSizeLabel: "Duplicate Size",
+  ghostFileTitle: "Added Then Removed Files",
+  tocLabel: "TOC",
+  errorLabel: "Error",
+  incompleteTips: "Incomplete analysis, failed layers: ",
+  diffIdMismatchTips: "Integrity check fail, diff id mismatch layers: ",
+  diffIdUnverifiedTips: "Integrity check skipped, unverified layers: ",
+  contentSkippedTips:
+    "ELF binaries and language components are not analyzed(listed from toc), layers: ",
+  baseImageLabel: "Base Image",
+  appSizeLabel: "Application Layer Size",
+  packageCacheTitle: "Package Manager Caches",
+  lintTips: "Lint check fail: ",
+  secretTips: "Secret found: ",
+  sbomTitle: "Software Bill of Materials",
+  typeLabel: "Type",
+  packageLabel: "Package",
+  componentTitle: "Language Components",
+  componentLabel: "Component",
+  vulnerabilityTitle: "Vulnerabilities",
+  severityLabel: "Severity",
+  vulnerabilityLabel: "Vulnerability",
+  stripSavingsLabel: "Estimated Strip Savings",
+  unstrippedBinaryTitle: "Unstripped Binaries",
+  savingsLabel: "Savings",
+  fileTypeTitle: "Size By File Type",
+  fileTypeLabel: "File Type",
+  permissionTitle: "Permission Issues",
+  imageConfigTitle: "Image Config",
+  userLabel: "User",
+  workingDirLabel: "Working Dir",
+  exposedPortsLabel: "Exposed Ports",
+  volumesLabel: "Volumes",
+  reproducibilityTitle: "Reproducibility",
+  kindLabel: "Kind",
+  zstdSavingsLabel: "Estimated Zstd Savings",
+  compressionLabel: "Compression",
 };
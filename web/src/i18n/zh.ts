@@ -18,6 +18,7 @@ export default {
   layerLabel: "分层",
   modificationLabel: "更新或删除",
   expandLabel: "展开所有目录",
+  aggregatedLabel: "叠加视图",
   keywordsLabel: "关键字",
   wastedSummaryTitle: "浪费的空间概要",
   totalSizeLabel: "总大小",
@@ -25,4 +26,41 @@ export default {
   pathLabel: "路径",
   latestAnalyzeImagesTitle: "最近分析的镜像列表",
   modifiedAddedLargeFileTitle: "修改或新增的大文件",
+  duplicateFileTitle: "内容重复的文件",
+  duplicateSizeLabel: "重复文件占用的空间",
+  ghostFileTitle: "添加后又被删除的文件",
+  tocLabel: "TOC索引",
+  errorLabel: "出错",
+  incompleteTips: "分析结果不完整，解析失败的分层：",
+  diffIdMismatchTips: "完整性校验失败，diff id不一致的分层：",
+  diffIdUnverifiedTips: "未进行完整性校验的分层：",
+  contentSkippedTips: "使用toc未解压，未分析ELF与语言组件的分层：",
+  baseImageLabel: "基础镜像",
+  appSizeLabel: "应用层总大小",
+  packageCacheTitle: "包管理工具的缓存",
+  lintTips: "规则检测不通过：",
+  secretTips: "发现敏感信息：",
+  sbomTitle: "软件物料清单",
+  typeLabel: "类型",
+  packageLabel: "软件包",
+  componentTitle: "语言生态的组件",
+  componentLabel: "组件",
+  vulnerabilityTitle: "漏洞",
+  severityLabel: "级别",
+  vulnerabilityLabel: "漏洞",
+  stripSavingsLabel: "strip预计可节省",
+  unstrippedBinaryTitle: "未strip的二进制文件",
+  savingsLabel: "可节省",
+  fileTypeTitle: "按文件类型统计",
+  fileTypeLabel: "文件类型",
+  permissionTitle: "权限问题",
+  imageConfigTitle: "镜像配置",
+  userLabel: "运行用户",
+  workingDirLabel: "工作目录",
+  exposedPortsLabel: "暴露端口",
+  volumesLabel: "挂载卷",
+  reproducibilityTitle: "可重现构建",
+  kindLabel: "类型",
+  zstdSavingsLabel: "zstd预计可节省",
+  compressionLabel: "压缩率",
 };